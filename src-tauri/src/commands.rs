@@ -1,17 +1,25 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use jsonschema::JSONSchema;
+use jsonschema::{error::ValidationErrorKind, JSONSchema};
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
-use tempfile::NamedTempFile;
-use std::io::Write;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdout, Command};
 use std::fs;
-use std::path::PathBuf;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::instances::{parse_instance, InstanceRecord};
+use crate::json_spans::{escape_pointer_token, parse_spans, Position, ValueSpan};
+use crate::languages::{get_language, LanguageDefinition, LanguageRegistry, REGISTRY};
+use crate::mirrors::update_mirror;
+use crate::log_parsers::{summarize, LogParser, RegexParser, RegexParserSpec, TestResult, AGENTIC_PARSER, REGEX_PARSER};
+use crate::patches::{apply_script, encode_patches, PatchFailure, PatchFailureDetector};
+use crate::spec_format::{parse_spec, serialize_spec, SpecFormat};
+use crate::spec_migration::{migrate_spec_value, MigrationResult, CURRENT_SPEC_VERSION};
+use crate::verification::{patch_failed_report, report, verdicts, TestVerdict};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DockerSpecs {
@@ -19,29 +27,96 @@ pub struct DockerSpecs {
     pub node_version: Option<String>,
     pub pnpm_version: Option<String>,
     pub rust_version: Option<String>,
+    pub python_version: Option<String>,
+    pub poetry_version: Option<String>,
+    pub uv_version: Option<String>,
+    pub go_version: Option<String>,
+    pub jdk_version: Option<String>,
+    pub maven_version: Option<String>,
+    pub gradle_version: Option<String>,
+    pub system_packages: Option<Vec<String>>,
+    pub base_image: Option<String>,
+    // Keys declared by custom languages
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TestConfig {
+    pub spec_version: Option<u64>,
     pub test_cmd: String,
     pub log_parser_name: String,
+    // The patterns for log_parser_name "regex"
+    pub log_parser: Option<RegexParserSpec>,
+    // The executable for log_parser_name "agentic", instead of the one in the settings
+    pub log_parser_script: Option<String>,
     pub pre_install: Option<Vec<String>>,
     pub install: Option<Vec<String>>,
     pub build: Option<Vec<String>>,
+    pub env: Option<BTreeMap<String, String>>,
     pub docker_specs: Option<DockerSpecs>,
 }
 
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// A problem in the spec with its location in the source text, 1-based and with an
+// exclusive end so the editor can underline it directly
+#[derive(Serialize, Clone, Debug)]
+pub struct ValidationIssue {
+    pub pointer: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String,
+    pub severity: Severity,
+    // Set for lint warnings
+    pub rule: Option<String>,
+    pub suggestion: Option<String>,
+}
+
+// Why a spec was rejected, as text for the error banner and as issues for the editor
+pub struct SpecError {
+    pub message: String,
+    pub issues: Vec<ValidationIssue>,
+}
+
+// Where the repository in /testbed comes from
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RepoSource {
+    // Clone github_repo_url inside the image
+    #[default]
+    Clone,
+    // Export the commit from a repository on this machine, bare or not
+    Local { path: String },
+    // Like Local, from a mirror of github_repo_url kept in ~/.swebench-debugger/mirrors
+    Mirror,
+}
+
 #[derive(Serialize)]
 pub struct ValidationResult {
     pub success: bool,
     pub error: Option<String>,
     pub dockerfile: Option<String>,
+    // Files the Dockerfile COPYs, build_docker_image puts them in the build context
+    pub context_files: BTreeMap<String, String>,
+    pub issues: Vec<ValidationIssue>,
+    // The format the spec was read as, None when it couldn't be parsed
+    pub input_format: Option<SpecFormat>,
 }
 
 type TabId = String;
 lazy_static::lazy_static! {
     static ref DOCKER_PROCESSES: Arc<Mutex<HashMap<TabId, Child>>> = Arc::new(Mutex::new(HashMap::new()));
     static ref TEST_PROCESSES: Arc<Mutex<HashMap<TabId, Child>>> = Arc::new(Mutex::new(HashMap::new()));
+    // config.json as last read or saved, validation looks values up on every keystroke
+    static ref CONFIG: Mutex<Option<Value>> = Mutex::new(None);
 }
 
 #[derive(Serialize, Clone)]
@@ -56,232 +131,963 @@ pub struct TestCompleteEvent {
     pub error: Option<String>,
 }
 
+// Names a shell can export, which is what the setup script and test_cmd run in
+const ENV_NAME_PATTERN: &str = "^[A-Za-z_][A-Za-z0-9_]*$";
+
 fn get_json_schema(language: &str) -> Value {
-    match language {
-        "Rust" => json!({
-            "type": "object",
-            "properties": {
-                "test_cmd": {
-                    "type": "string",
-                    "description": "The test command without any placeholders, all tests are getting appended to the end of this test command."
-                },
-                "log_parser_name": {
-                    "type": "string",
-                    "enum": ["cargo", "agentic"],
-                    "description": "A log parser is used to parse the textual result of the tests and determine how many succeeded, how many failed..."
-                },
-                "pre_install": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run before the installation step."
-                },
-                "install": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run in the installation step."
-                },
-                "build": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run in the build step."
-                },
-                "docker_specs": {
-                    "type": "object",
-                    "properties": {
-                        "rust_version": {
-                            "type": "string",
-                            "description": "Rust version to use"
-                        }
-                    },
-                    "required": [],
-                    "additionalProperties": false
-                }
+    let language = get_language(language);
+
+    // Every language takes a base image and extra packages on top of its own docker specs
+    let mut docker_specs_properties = language.docker_specs.clone();
+    let mut packages_description = "Extra system packages to install in the image's package layer.".to_string();
+    if !language.system_packages.is_empty() {
+        packages_description.push_str(&format!(" Already provided: {}.", language.system_packages.join(", ")));
+    }
+    docker_specs_properties.insert("system_packages".to_string(), json!({
+        "type": "array",
+        "items": { "type": "string", "pattern": "^[a-zA-Z0-9][a-zA-Z0-9+.:~_=-]*$" },
+        "description": packages_description
+    }));
+    docker_specs_properties.insert("base_image".to_string(), json!({
+        "type": "string",
+        "pattern": "^[^\\s]+$",
+        "description": "Image to build FROM instead of the language's default, e.g. node:18-bullseye or nvidia/cuda:12.2.0-devel-ubuntu22.04. The image should be Debian or Ubuntu based, the Rust, Go, Python and Java images also work with Alpine based images."
+    }));
+
+    // Any language can describe its output with patterns instead
+    let mut log_parsers = language.log_parsers.clone();
+    if !log_parsers.iter().any(|parser| parser == REGEX_PARSER) {
+        log_parsers.push(REGEX_PARSER.to_string());
+    }
+
+    json!({
+        "type": "object",
+        "properties": {
+            "spec_version": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": CURRENT_SPEC_VERSION,
+                "description": "Version of the spec format, older specs are migrated to the current version before validation. Specs without it are version 1."
             },
-            "required": ["test_cmd", "log_parser_name"],
-            "additionalProperties": false
-        }),
-        "C/CPP" => json!({
-            "type": "object",
-            "properties": {
-                "test_cmd": {
-                    "type": "string",
-                    "description": "The test command without any placeholders, all tests are getting appended to the end of this test command."
-                },
-                "log_parser_name": {
-                    "type": "string",
-                    "enum": ["doctest", "googletest", "agentic"],
-                    "description": "A log parser is used to parse the textual result of the tests and determine how many succeeded, how many failed..."
-                },
-                "pre_install": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run before the installation step."
-                },
-                "install": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run in the installation step."
-                },
-                "build": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run in the build step."
-                },
-                "docker_specs": {
-                    "type": "object",
-                    "properties": {
-                        "ubuntu_version": {
-                            "type": "string",
-                            "description": "Ubuntu version for the Docker image"
-                        }
-                    },
-                    "required": [],
-                    "additionalProperties": false
-                }
+            "test_cmd": {
+                "type": "string",
+                "description": "The test command without any placeholders, all tests are getting appended to the end of this test command."
             },
-            "required": ["test_cmd", "log_parser_name"],
-            "additionalProperties": false
-        }),
-        _ => json!({
-            "type": "object",
-            "properties": {
-                "test_cmd": {
-                    "type": "string",
-                    "description": "The test command without any placeholders, all tests are getting appended to the end of this test command."
-                },
-                "log_parser_name": {
-                    "type": "string",
-                    "enum": ["jest", "mocha", "vitest", "karma", "tap", "calypso", "chartjs", "marked", "p5js", "agentic"],
-                    "description": "A log parser is used to parse the textual result of the tests and determine how many succeeded, how many failed..."
-                },
-                "pre_install": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run before the installation step."
-                },
-                "install": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run in the installation step."
-                },
-                "build": {
-                    "type": "array",
-                    "items": { "type": "string" },
-                    "description": "List of regular shell commands to run in the build step."
-                },
-                "docker_specs": {
-                    "type": "object",
-                    "properties": {
-                        "ubuntu_version": {
-                            "type": "string",
-                            "description": "Ubuntu version for the Docker image"
-                        },
-                        "node_version": {
-                            "type": "string",
-                            "description": "Node.js version to install"
+            "log_parser_name": {
+                "type": "string",
+                "enum": log_parsers,
+                "description": "A log parser is used to parse the textual result of the tests and determine how many succeeded, how many failed..."
+            },
+            "log_parser": {
+                "type": "object",
+                "properties": {
+                    "passed": { "type": "string", "description": "Regex matching the line of a passing test, with a (?P<name>...) group for its name and optionally a (?P<duration>...) group." },
+                    "failed": { "type": "string", "description": "Regex matching the line of a failing test, with a (?P<name>...) group for its name and optionally a (?P<duration>...) group." },
+                    "skipped": { "type": "string", "description": "Regex matching the line of a skipped test, with a (?P<name>...) group for its name." },
+                    "name_normalizer": {
+                        "type": "object",
+                        "properties": {
+                            "pattern": { "type": "string", "description": "Regex replaced in every captured test name." },
+                            "replacement": { "type": "string", "description": "What the pattern is replaced with, $1 or ${group} refer to its groups. Defaults to removing the match." }
                         },
-                        "pnpm_version": {
-                            "type": "string",
-                            "description": "PNPM version to install"
-                        }
-                    },
-                    "required": [],
-                    "additionalProperties": false
-                }
+                        "required": ["pattern"],
+                        "additionalProperties": false
+                    }
+                },
+                "additionalProperties": false,
+                "description": "Patterns for the \"regex\" log parser, each matched against every line of the test output. The first of passed, failed and skipped that matches a line decides the test's status."
             },
-            "required": ["test_cmd", "log_parser_name"],
-            "additionalProperties": false
-        })
+            "log_parser_script": {
+                "type": "string",
+                "description": "Path to an executable the \"agentic\" log parser runs with the test output on stdin, printing a JSON list of test results. Overrides the parser script in the settings."
+            },
+            "pre_install": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "List of regular shell commands to run before the installation step."
+            },
+            "install": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "List of regular shell commands to run in the installation step."
+            },
+            "build": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "List of regular shell commands to run in the build step."
+            },
+            "env": {
+                "type": "object",
+                "propertyNames": { "pattern": ENV_NAME_PATTERN },
+                "additionalProperties": { "type": "string", "pattern": "^[^\\r\\n]*$" },
+                "description": "Environment variables baked into the image, they apply to the setup commands and the test run. Values can reference other variables as $NAME."
+            },
+            "docker_specs": {
+                "type": "object",
+                "properties": docker_specs_properties,
+                "required": [],
+                "additionalProperties": false
+            }
+        },
+        "required": ["test_cmd", "log_parser_name"],
+        "additionalProperties": false
+    })
+}
+
+#[derive(Clone, Copy)]
+enum SpanTarget {
+    Value,
+    // The member's key, or the opening bracket for the root value
+    Key,
+}
+
+fn issue_at(
+    spans: &HashMap<String, ValueSpan>,
+    pointer: &str,
+    target: SpanTarget,
+    message: String,
+    severity: Severity
+) -> ValidationIssue {
+    // Migrated specs have values the source text doesn't, like /install/0 for the
+    // `install` string of a version 1 spec. Those point at the closest member it has.
+    let mut pointer = pointer;
+    while !spans.is_empty() && !spans.contains_key(pointer) {
+        match pointer.rfind('/') {
+            Some(index) => pointer = &pointer[..index],
+            None => break,
+        }
+    }
+    let (start, end) = match spans.get(pointer) {
+        Some(span) => match (target, span.key) {
+            (SpanTarget::Key, Some(key)) => key,
+            (SpanTarget::Key, None) => (span.start, Position { line: span.start.line, column: span.start.column + 1 }),
+            (SpanTarget::Value, _) => (span.start, span.end),
+        },
+        None => (Position { line: 1, column: 1 }, Position { line: 1, column: 2 }),
+    };
+    ValidationIssue {
+        pointer: pointer.to_string(),
+        line: start.line,
+        column: start.column,
+        end_line: end.line,
+        end_column: end.column,
+        message,
+        severity,
+        rule: None,
+        suggestion: None,
+    }
+}
+
+// Property names the schema allows on the object at `pointer`
+fn allowed_properties(schema: &Value, pointer: &str) -> Vec<String> {
+    let mut schema = schema;
+    for token in pointer.split('/').skip(1) {
+        let next = if token.parse::<usize>().is_ok() && schema.get("items").is_some() {
+            schema.get("items")
+        } else {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            schema.get("properties").and_then(|properties| properties.get(token.as_str()))
+        };
+        match next {
+            Some(next) => schema = next,
+            None => return Vec::new(),
+        }
+    }
+    schema
+        .get("properties")
+        .and_then(|properties| properties.as_object())
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+// Optimal string alignment distance, Levenshtein plus swapped neighbours since
+// "jset" and "node_verison" are the typos people actually make
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+// The closest candidate, if it's close enough to be a typo of `input`
+fn closest_match<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (input.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&input.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+// Span tracking only understands strict JSON, issues in other formats point at the start
+fn source_spans(input: &str, format: SpecFormat) -> HashMap<String, ValueSpan> {
+    if format == SpecFormat::Json {
+        parse_spans(input)
+    } else {
+        HashMap::new()
     }
 }
 
-fn validate_json_config(input: &str, language: &str) -> Result<TestConfig, String> {
-    // First, try to parse the JSON
-    let parsed_json: Value = match serde_json::from_str(input) {
-        Ok(json) => json,
-        Err(e) => return Err(format!("Invalid JSON syntax: {}", e)),
+fn validate_json_config(input: &str, language: &str, format: Option<SpecFormat>) -> Result<(TestConfig, SpecFormat), SpecError> {
+    // First, try to parse the spec, YAML, TOML and JSON5 specs are validated as their JSON equivalent
+    let (mut parsed_json, format) = match parse_spec(input, format) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(SpecError {
+                message: format!("Invalid {} syntax: {}", e.format.label(), e.message),
+                issues: vec![ValidationIssue {
+                    pointer: String::new(),
+                    line: e.line,
+                    column: e.column,
+                    end_line: e.line,
+                    end_column: e.column + 1,
+                    message: e.message,
+                    severity: Severity::Error,
+                    rule: None,
+                    suggestion: None,
+                }],
+            });
+        }
     };
 
+    // Upgrade specs saved in an older shape
+    if let Err(e) = migrate_spec_value(&mut parsed_json) {
+        let spans = source_spans(input, format);
+        return Err(SpecError {
+            message: format!("Migration failed: {}", e),
+            issues: vec![issue_at(&spans, "/spec_version", SpanTarget::Value, e, Severity::Error)],
+        });
+    }
+
     // Get the schema and compile it
     let schema_value = get_json_schema(language);
     let schema = match JSONSchema::compile(&schema_value) {
         Ok(schema) => schema,
-        Err(e) => return Err(format!("Schema compilation error: {}", e)),
+        Err(e) => return Err(SpecError { message: format!("Schema compilation error: {}", e), issues: Vec::new() }),
     };
 
     // Validate against schema
     let validation_result = schema.validate(&parsed_json);
     if let Err(errors) = validation_result {
+        let spans = source_spans(input, format);
         let mut error_messages = Vec::new();
+        let mut issues = Vec::new();
         for error in errors {
-            let path = if error.instance_path.to_string().is_empty() {
+            let pointer = error.instance_path.to_string();
+            let path = if pointer.is_empty() {
                 "root".to_string()
             } else {
-                error.instance_path.to_string()
+                pointer.clone()
             };
-            error_messages.push(format!("At '{}': {}", path, error));
+            let mut error_message = format!("At '{}': {}", path, error);
+
+            match &error.kind {
+                // Point at each offending key rather than at the whole object
+                ValidationErrorKind::AdditionalProperties { unexpected } => {
+                    let allowed = allowed_properties(&schema_value, &pointer);
+                    let present = error.instance.as_object();
+                    // Don't suggest keys the object already has
+                    let candidates: Vec<&str> = allowed
+                        .iter()
+                        .map(|key| key.as_str())
+                        .filter(|key| !present.map(|object| object.contains_key(*key)).unwrap_or(false))
+                        .collect();
+                    for key in unexpected {
+                        let key_pointer = format!("{}/{}", pointer, escape_pointer_token(key));
+                        let mut issue = issue_at(&spans, &key_pointer, SpanTarget::Key, format!("Additional property '{}' is not allowed", key), Severity::Error);
+                        if let Some(suggestion) = closest_match(key, candidates.iter().copied()) {
+                            error_message.push_str(&format!(" Did you mean `{}` instead of `{}`?", suggestion, key));
+                            issue.suggestion = Some(format!("Did you mean `{}`?", suggestion));
+                        }
+                        issues.push(issue);
+                    }
+                }
+                // Only env names are restricted, underline the offending name
+                ValidationErrorKind::PropertyNames { error: name_error } => {
+                    let name = name_error.instance.as_str().unwrap_or_default();
+                    let message = format!(
+                        "'{}' is not a valid environment variable name, names can only contain letters, digits and underscores and can't start with a digit",
+                        name
+                    );
+                    let name_pointer = format!("{}/{}", pointer, escape_pointer_token(name));
+                    error_message = format!("At '{}': {}", path, message);
+                    issues.push(issue_at(&spans, &name_pointer, SpanTarget::Key, message, Severity::Error));
+                }
+                ValidationErrorKind::Required { .. } => {
+                    issues.push(issue_at(&spans, &pointer, SpanTarget::Key, error.to_string(), Severity::Error));
+                }
+                ValidationErrorKind::Enum { options } => {
+                    let mut issue = issue_at(&spans, &pointer, SpanTarget::Value, error.to_string(), Severity::Error);
+                    let candidates = options.as_array().into_iter().flatten().filter_map(|option| option.as_str());
+                    if let Some(suggestion) = error.instance.as_str().and_then(|value| closest_match(value, candidates)) {
+                        error_message.push_str(&format!(" Did you mean `{}`?", suggestion));
+                        issue.suggestion = Some(format!("Did you mean `{}`?", suggestion));
+                    }
+                    issues.push(issue);
+                }
+                _ => {
+                    issues.push(issue_at(&spans, &pointer, SpanTarget::Value, error.to_string(), Severity::Error));
+                }
+            }
+            error_messages.push(error_message);
         }
-        return Err(format!("Validation failed:\n{}", error_messages.join("\n")));
+        return Err(SpecError {
+            message: format!("Validation failed:\n{}", error_messages.join("\n")),
+            issues,
+        });
     }
 
     // If validation passes, try to deserialize into our struct
-    match serde_json::from_value::<TestConfig>(parsed_json.clone()) {
-        Ok(config) => Ok(config),
-        Err(e) => Err(format!("Failed to parse validated JSON: {}", e)),
+    let config = match serde_json::from_value::<TestConfig>(parsed_json.clone()) {
+        Ok(config) => config,
+        Err(e) => return Err(SpecError { message: format!("Failed to parse validated JSON: {}", e), issues: Vec::new() }),
+    };
+
+    // The schema can't tell whether the regex parser's patterns compile
+    if config.log_parser_name == REGEX_PARSER {
+        let problem = match &config.log_parser {
+            Some(spec) => RegexParser::compile(spec).err(),
+            None => Some((
+                "/log_parser_name".to_string(),
+                "log_parser_name \"regex\" needs a log_parser object with passed, failed or skipped patterns".to_string(),
+            )),
+        };
+        if let Some((pointer, message)) = problem {
+            let spans = source_spans(input, format);
+            return Err(SpecError {
+                message: format!("Validation failed:\nAt '{}': {}", pointer, message),
+                issues: vec![issue_at(&spans, &pointer, SpanTarget::Value, message, Severity::Error)],
+            });
+        }
     }
+    Ok((config, format))
 }
 
-fn generate_dockerfile(config: &TestConfig, github_repo_url: &str, commit: &str, language: &str) -> String {
-    // Format arrays for printf command - ["cmd1", "cmd2"] => "cmd1" "cmd2"
-    let format_commands = |cmds: &Option<Vec<String>>| -> String {
-        cmds.as_ref()
-            .map(|commands| {
-                if commands.is_empty() {
-                    String::new()
-                } else {
-                    commands.iter()
-                        .map(|cmd| format!("\"{}\"", cmd))
-                        .collect::<Vec<_>>()
-                        .join(" ")
-                }
-            })
-            .unwrap_or_else(String::new)
+struct LintWarning {
+    pointer: String,
+    target: SpanTarget,
+    message: String,
+    suggestion: Option<String>,
+}
+
+type LintRule = fn(&TestConfig, &LanguageDefinition) -> Vec<LintWarning>;
+
+// Checks for specs that pass the schema but are still wrong. Each rule can be turned
+// off by adding its id to the comma separated `disabled_lint_rules` config value.
+const LINT_RULES: &[(&str, LintRule)] = &[
+    ("log-parser-mismatch", lint_log_parser_mismatch),
+    ("test-cmd-placeholder", lint_test_cmd_placeholder),
+    ("duplicate-install-command", lint_duplicate_install_command),
+    ("docker-spec-version", lint_docker_spec_version),
+    ("system-package-provided", lint_system_package_provided),
+    ("base-image-compatibility", lint_base_image_compatibility),
+];
+
+fn lint_config(input: &str, format: SpecFormat, config: &TestConfig, language: &str) -> Vec<ValidationIssue> {
+    let disabled_rules = load_config("disabled_lint_rules".to_string()).unwrap_or_default();
+    run_lint_rules(input, format, config, language, &disabled_rules)
+}
+
+// The warnings of every rule not listed in the comma separated `disabled_rules`
+fn run_lint_rules(input: &str, format: SpecFormat, config: &TestConfig, language: &str, disabled_rules: &str) -> Vec<ValidationIssue> {
+    let language = get_language(language);
+    let disabled_rules: Vec<&str> = disabled_rules.split(',').map(|rule| rule.trim()).collect();
+
+    let spans = source_spans(input, format);
+    let mut issues = Vec::new();
+    for (rule, check) in LINT_RULES {
+        if disabled_rules.contains(rule) {
+            continue;
+        }
+        for warning in check(config, language) {
+            let mut issue = issue_at(&spans, &warning.pointer, warning.target, warning.message, Severity::Warning);
+            issue.rule = Some(rule.to_string());
+            issue.suggestion = warning.suggestion;
+            issues.push(issue);
+        }
+    }
+    issues
+}
+
+// The test runner a command invokes, going by the executable names in it
+fn detect_test_runner(test_cmd: &str) -> Option<&'static str> {
+    const RUNNERS: &[&str] = &["jest", "mocha", "vitest", "karma", "tap", "pytest", "unittest"];
+    test_cmd
+        .split(|c: char| c.is_whitespace() || c == ';' || c == '&' || c == '|')
+        .map(|token| token.rsplit('/').next().unwrap_or(token))
+        .find_map(|token| RUNNERS.iter().find(|runner| **runner == token).copied())
+}
+
+fn lint_log_parser_mismatch(config: &TestConfig, language: &LanguageDefinition) -> Vec<LintWarning> {
+    let runner = match detect_test_runner(&config.test_cmd) {
+        Some(runner) => runner,
+        None => return Vec::new(),
     };
+    // Only flag runners this language has a parser for, agentic and regex handle anything
+    if config.log_parser_name == runner
+        || config.log_parser_name == AGENTIC_PARSER
+        || config.log_parser_name == REGEX_PARSER
+        || !language.log_parsers.iter().any(|parser| parser == runner)
+    {
+        return Vec::new();
+    }
+    vec![LintWarning {
+        pointer: "/log_parser_name".to_string(),
+        target: SpanTarget::Value,
+        message: format!("test_cmd runs {} but log_parser_name is '{}'", runner, config.log_parser_name),
+        suggestion: Some(format!("Set log_parser_name to \"{}\"", runner)),
+    }]
+}
 
-    let pre_install_cmds = format_commands(&config.pre_install);
-    let install_cmds = format_commands(&config.install);
-    let build_cmds = format_commands(&config.build);
-    
-    match language {
-        "C/CPP" => generate_cpp_dockerfile(config, github_repo_url, commit, &pre_install_cmds, &install_cmds, &build_cmds),
-        "Rust" => generate_rust_dockerfile(config, github_repo_url, commit, &pre_install_cmds, &install_cmds, &build_cmds),
-        _ => generate_js_dockerfile(config, github_repo_url, commit, &pre_install_cmds, &install_cmds, &build_cmds), // Default to JS
+fn lint_test_cmd_placeholder(config: &TestConfig, _language: &LanguageDefinition) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut rest = config.test_cmd.as_str();
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else { break };
+        let placeholder = &rest[start..start + length + 1];
+        let name = &placeholder[1..placeholder.len() - 1];
+        // ${VAR} is the shell expanding a variable, not a placeholder
+        let shell_variable = rest[..start].ends_with('$');
+        if !shell_variable && !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            warnings.push(LintWarning {
+                pointer: "/test_cmd".to_string(),
+                target: SpanTarget::Value,
+                message: format!("test_cmd contains the placeholder {}, placeholders are not substituted", placeholder),
+                suggestion: Some(format!("Remove {}, the test files are appended to the end of test_cmd", placeholder)),
+            });
+        }
+        rest = &rest[start + length + 1..];
     }
+    warnings
 }
 
-fn generate_js_dockerfile(
-    config: &TestConfig, 
-    github_repo_url: &str, 
+fn lint_duplicate_install_command(config: &TestConfig, _language: &LanguageDefinition) -> Vec<LintWarning> {
+    let pre_install = config.pre_install.as_deref().unwrap_or_default();
+    let install = config.install.as_deref().unwrap_or_default();
+    install
+        .iter()
+        .enumerate()
+        .filter(|(_, cmd)| pre_install.iter().any(|pre| pre.trim() == cmd.trim()))
+        .map(|(index, cmd)| LintWarning {
+            pointer: format!("/install/{}", index),
+            target: SpanTarget::Value,
+            message: format!("'{}' already runs in pre_install", cmd),
+            suggestion: Some("Remove the command from install".to_string()),
+        })
+        .collect()
+}
+
+fn lint_docker_spec_version(config: &TestConfig, _language: &LanguageDefinition) -> Vec<LintWarning> {
+    // Release channels the base images and installers understand besides numbers
+    const CHANNELS: &[&str] = &["latest", "stable", "beta", "nightly", "lts"];
+    let specs = match config.docker_specs.as_ref().and_then(|s| serde_json::to_value(s).ok()) {
+        Some(Value::Object(specs)) => specs,
+        _ => return Vec::new(),
+    };
+    specs
+        .iter()
+        .filter(|(key, _)| key.ends_with("_version"))
+        .filter_map(|(key, value)| value.as_str().map(|version| (key, version)))
+        .filter(|(_, version)| {
+            !version.starts_with(|c: char| c.is_ascii_digit()) && !CHANNELS.contains(&version.to_lowercase().as_str())
+        })
+        .map(|(key, version)| LintWarning {
+            pointer: format!("/docker_specs/{}", escape_pointer_token(key)),
+            target: SpanTarget::Value,
+            message: format!("{} '{}' doesn't look like a version number", key, version),
+            suggestion: Some(format!("Use a version number for {}, e.g. \"20\" or \"3.11\"", key)),
+        })
+        .collect()
+}
+
+fn lint_system_package_provided(config: &TestConfig, language: &LanguageDefinition) -> Vec<LintWarning> {
+    let packages = config.docker_specs.as_ref().and_then(|s| s.system_packages.as_deref()).unwrap_or_default();
+    packages
+        .iter()
+        .enumerate()
+        .filter(|(_, package)| language.system_packages.contains(package))
+        .map(|(index, package)| LintWarning {
+            pointer: format!("/docker_specs/system_packages/{}", index),
+            target: SpanTarget::Value,
+            message: format!("The {} image already installs {}", language.name, package),
+            suggestion: Some("Remove the package from system_packages".to_string()),
+        })
+        .collect()
+}
+
+fn lint_base_image_compatibility(config: &TestConfig, language: &LanguageDefinition) -> Vec<LintWarning> {
+    // Custom templates install their packages however they like
+    if language.dockerfile_template.is_some() {
+        return Vec::new();
+    }
+    let image = match config.docker_specs.as_ref().and_then(|s| s.base_image.as_deref()) {
+        Some(image) => image,
+        None => return Vec::new(),
+    };
+    let warning = |message: String, suggestion: &str| LintWarning {
+        pointer: "/docker_specs/base_image".to_string(),
+        target: SpanTarget::Value,
+        message,
+        suggestion: Some(suggestion.to_string()),
+    };
+    match PackageManager::for_image(Some(image)) {
+        PackageManager::Other => vec![warning(
+            format!("'{}' doesn't look Debian, Ubuntu or Alpine based, the generated Dockerfile installs packages with apt", image),
+            "Use a Debian, Ubuntu or Alpine based image",
+        )],
+        PackageManager::Apk if !APK_LANGUAGES.contains(&language.name.as_str()) => vec![warning(
+            format!("The {} Dockerfile installs its toolchain with apt, which the Alpine based '{}' doesn't have", language.name, image),
+            "Use a Debian or Ubuntu based image",
+        )],
+        PackageManager::Apt if language.name == "Javascript" => {
+            let mut warnings = Vec::new();
+            let node_version = config.docker_specs.as_ref().and_then(|s| s.node_version.as_deref());
+            if let (true, Some(node_version)) = (image_provides_node(image), node_version) {
+                warnings.push(LintWarning {
+                    pointer: "/docker_specs/node_version".to_string(),
+                    target: SpanTarget::Value,
+                    message: format!("node_version {} is ignored, '{}' brings its own Node", node_version, image),
+                    suggestion: Some("Remove node_version or pick the Node version with the image tag".to_string()),
+                });
+            }
+            if image_mentions(image, NO_PYTHON2_RELEASES) {
+                warnings.push(warning(
+                    format!("The Javascript Dockerfile installs python2, which '{}' doesn't package", image),
+                    "Use a bullseye or Ubuntu 22.04 based image",
+                ));
+            }
+            warnings
+        }
+        // The spec's packages are translated to apk, say where that changed them
+        PackageManager::Apk => {
+            let packages = config.docker_specs.as_ref().and_then(|s| s.system_packages.as_deref()).unwrap_or_default();
+            packages
+                .iter()
+                .enumerate()
+                .filter_map(|(index, package)| apk_package(package).note.map(|note| (index, note)))
+                .map(|(index, note)| LintWarning {
+                    pointer: format!("/docker_specs/system_packages/{}", index),
+                    target: SpanTarget::Value,
+                    message: format!("{} on the Alpine based '{}'", note, image),
+                    suggestion: Some("Use the Alpine package name, pinned like name=1.2.3-r0 if needed".to_string()),
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+// Languages whose Dockerfile only needs the package layer switched to work on Alpine
+const APK_LANGUAGES: &[&str] = &["Rust", "Go", "Python", "Java"];
+
+// Alpine names for Debian packages the built-in languages and specs commonly install,
+// None when Alpine doesn't need the package. Other names are the same on both.
+const APK_PACKAGES: &[(&str, Option<&str>)] = &[
+    ("build-essential", Some("build-base")),
+    ("libssl-dev", Some("openssl-dev")),
+    ("pkg-config", Some("pkgconf")),
+    ("python3-pip", Some("py3-pip")),
+    ("python3-dev", Some("python3-dev")),
+    ("zlib1g-dev", Some("zlib-dev")),
+    ("libbz2-dev", Some("bzip2-dev")),
+    ("libsqlite3-dev", Some("sqlite-dev")),
+    ("libreadline-dev", Some("readline-dev")),
+    ("libyaml-dev", Some("yaml-dev")),
+    ("libjpeg-dev", Some("libjpeg-turbo-dev")),
+    ("xz-utils", Some("xz")),
+    ("netcat", Some("netcat-openbsd")),
+    ("python3-venv", None),
+    ("python-is-python3", None),
+    ("software-properties-common", None),
+    ("apt-transport-https", None),
+];
+
+// How apk installs a package given with its Debian name
+struct ApkPackage {
+    // None when Alpine doesn't need the package
+    name: Option<String>,
+    // Why the package isn't installed as written, for base-image-compatibility
+    note: Option<String>,
+}
+
+fn apk_package(package: &str) -> ApkPackage {
+    let (name, version) = match package.split_once('=') {
+        Some((name, version)) => (name, Some(version)),
+        None => (package, None),
+    };
+    let apk_name = match APK_PACKAGES.iter().find(|(apt, _)| *apt == name) {
+        Some((_, Some(apk))) => apk.to_string(),
+        Some((_, None)) => {
+            return ApkPackage { name: None, note: Some(format!("Alpine doesn't need {}, it isn't installed", name)) };
+        }
+        None => name.to_string(),
+    };
+    match version {
+        // apk pins look like 2.43.0-r0, epochs, `~` and Debian revisions are apt only
+        Some(version) if is_apk_version(version) => ApkPackage { name: Some(format!("{}={}", apk_name, version)), note: None },
+        Some(_) => ApkPackage {
+            note: Some(format!("{} pins an apt version, {} is installed without the pin", package, apk_name)),
+            name: Some(apk_name),
+        },
+        None => ApkPackage { name: Some(apk_name), note: None },
+    }
+}
+
+fn is_apk_version(version: &str) -> bool {
+    let (version, revision) = version.rsplit_once('-').unwrap_or((version, "r0"));
+    version.starts_with(|c: char| c.is_ascii_digit())
+        && version.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+        && revision.strip_prefix('r').map(|r| !r.is_empty() && r.chars().all(|c| c.is_ascii_digit())).unwrap_or(false)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PackageManager {
+    Apt,
+    Apk,
+    // RPM based and other images the generators can't install packages on
+    Other,
+}
+
+impl PackageManager {
+    // Going by the image name, anything not recognisably Alpine or RPM based is taken
+    // to be apt based, like the default images
+    fn for_image(image: Option<&str>) -> PackageManager {
+        const OTHER_DISTROS: &[&str] = &["fedora", "centos", "rockylinux", "almalinux", "ubi", "amazonlinux", "oraclelinux", "opensuse", "archlinux"];
+        let image = match image {
+            Some(image) => image,
+            None => return PackageManager::Apt,
+        };
+        if image_mentions(image, &["alpine"]) {
+            PackageManager::Apk
+        } else if image_mentions(image, OTHER_DISTROS) {
+            PackageManager::Other
+        } else {
+            PackageManager::Apt
+        }
+    }
+
+    // The language's packages under the names this package manager knows them by
+    fn provided_packages(self, packages: &[String]) -> Vec<String> {
+        match self {
+            // The setup script runs with bash, which Alpine doesn't ship
+            PackageManager::Apk => std::iter::once("bash".to_string()).chain(self.package_names(packages)).collect(),
+            _ => packages.to_vec(),
+        }
+    }
+
+    // Packages given with their Debian names, as this package manager installs them
+    fn package_names(self, packages: &[String]) -> Vec<String> {
+        match self {
+            PackageManager::Apk => packages.iter().filter_map(|package| apk_package(package).name).collect(),
+            _ => packages.to_vec(),
+        }
+    }
+
+    // The package layer and the unprivileged user
+    fn base_layer(self, packages: &[String]) -> String {
+        match self {
+            PackageManager::Apk => format!("RUN apk add --no-cache \\
+{}
+
+RUN adduser -D -g 'dog' nonroot",
+                package_list(packages)
+            ),
+            _ => format!("RUN apt update && apt install -y \\
+{} \\
+    && rm -rf /var/lib/apt/lists/*
+
+RUN adduser --disabled-password --gecos 'dog' nonroot",
+                package_list(packages)
+            ),
+        }
+    }
+}
+
+// Whether a part of the image name, split at `/`, `:`, `-`, `.` and `@`, starts with one of `names`
+fn image_mentions(image: &str, names: &[&str]) -> bool {
+    image
+        .to_lowercase()
+        .split(['/', ':', '-', '.', '@'])
+        .any(|part| names.iter().any(|name| part.starts_with(name)))
+}
+
+// The official node images and the ones built on them already have Node installed
+fn image_provides_node(image: &str) -> bool {
+    let image = image.split('@').next().unwrap_or(image);
+    let repository = image.rsplit('/').next().unwrap_or(image);
+    repository.split(':').next() == Some("node")
+}
+
+// apt based images that aren't Ubuntu are taken to be Debian, like the node images
+fn is_ubuntu_image(image: &str) -> bool {
+    image_mentions(image, &["ubuntu", "noble", "jammy", "focal", "bionic"])
+}
+
+// Releases without a python2 package, which the Javascript image installs
+const NO_PYTHON2_RELEASES: &[&str] = &["bookworm", "trixie", "noble"];
+
+// What the generators need from the spec besides the docker specs, already
+// formatted for the Dockerfile
+struct RepoSetup<'a> {
+    github_repo_url: &'a str,
+    commit: &'a str,
+    // Steps that leave the repository at the commit in /testbed
+    checkout: String,
+    package_manager: PackageManager,
+    // The language's packages followed by the spec's extras
+    system_packages: Vec<String>,
+    // ENV lines, placed before the checkout so they apply to the setup script and the test run
+    env: String,
+    // docker_specs.base_image, which replaces the generated FROM
+    base_image: Option<&'a str>,
+}
+
+// Returns the Dockerfile and the files it COPYs from the build context, by file name.
+// The spec's commands go into setup_repo.sh as they are, so quotes, `$`, backticks and
+// backslashes reach bash untouched.
+fn generate_dockerfile(
+    config: &TestConfig,
+    github_repo_url: &str,
     commit: &str,
-    pre_install_cmds: &str,
-    install_cmds: &str,
-    build_cmds: &str
+    language: &str,
+    source: &RepoSource,
+) -> (String, BTreeMap<String, String>) {
+    let language = get_language(language);
+    let base_image = config.docker_specs.as_ref().and_then(|s| s.base_image.as_deref());
+    // Only switch package managers where the rest of the Dockerfile works with it,
+    // base-image-compatibility warns about the other cases
+    let package_manager = if language.dockerfile_template.is_none() && APK_LANGUAGES.contains(&language.name.as_str()) {
+        PackageManager::for_image(base_image)
+    } else {
+        PackageManager::Apt
+    };
+    let setup = RepoSetup {
+        github_repo_url,
+        commit,
+        checkout: checkout_steps(source, github_repo_url, commit),
+        package_manager,
+        system_packages: merge_system_packages(
+            &package_manager.provided_packages(&language.system_packages),
+            &package_manager.package_names(config.docker_specs.as_ref().and_then(|s| s.system_packages.as_deref()).unwrap_or_default()),
+        ),
+        env: format_env(&config.env),
+        base_image,
+    };
+
+    let mut context_files = BTreeMap::new();
+    let setup_commands = [&config.pre_install, &config.install, &config.build];
+    context_files.insert("setup_repo.sh".to_string(), setup_script(setup_commands.into_iter().flatten().flatten()));
+
+    let specs = resolve_docker_specs(config, language);
+    let dockerfile = if let Some(template) = &language.dockerfile_template {
+        render_dockerfile_template(template, &specs, &setup)
+    } else {
+        // Built-ins keep their generators, see LanguageDefinition
+        match language.name.as_str() {
+            "C/CPP" => generate_cpp_dockerfile(&specs, &setup),
+            "Rust" => generate_rust_dockerfile(&specs, &setup),
+            "Go" => generate_go_dockerfile(&specs, &setup),
+            "Java" => generate_java_dockerfile(&specs, &setup),
+            "Python" => generate_python_dockerfile(&specs, &setup),
+            _ => {
+                // Default to JS, which also runs a (so far empty) environment script
+                context_files.insert("setup_env.sh".to_string(), setup_script(std::iter::empty()));
+                generate_js_dockerfile(&specs, &setup)
+            }
+        }
+    };
+
+    let dockerfile = match base_image {
+        Some(image) => replace_base_image(&dockerfile, image),
+        None => dockerfile,
+    };
+    (dockerfile, context_files)
+}
+
+fn checkout_steps(source: &RepoSource, github_repo_url: &str, commit: &str) -> String {
+    match source {
+        RepoSource::Clone => format!("RUN git clone --depth 1 -o origin {} /testbed
+RUN chmod -R 777 /testbed
+RUN git fetch origin {}
+RUN git reset --hard {}
+RUN git remote remove origin",
+            github_repo_url,
+            commit,
+            commit
+        ),
+        // build_docker_image exports the commit to repo/ in the build context. Commit it
+        // again so git diff and git apply work like in a clone.
+        RepoSource::Local { .. } | RepoSource::Mirror => format!("COPY {}/ /testbed/
+RUN chmod -R 777 /testbed
+RUN git init -q && git add -A -f && git -c user.name=swebench -c user.email=swebench@localhost commit -q --no-verify -m \"{}\"",
+            LOCAL_REPO_DIR,
+            commit
+        ),
+    }
+}
+
+// A bash script running the commands in order, one per line
+fn setup_script<'a>(commands: impl Iterator<Item = &'a String>) -> String {
+    let mut script = "#!/bin/bash\nset -euxo pipefail\n".to_string();
+    for command in commands {
+        script.push_str(command);
+        script.push('\n');
+    }
+    script
+}
+
+// Swap the image of the first FROM line, keeping its flags and stage name. Later
+// stages of multi-stage templates are kept.
+fn replace_base_image(dockerfile: &str, image: &str) -> String {
+    let mut replaced = false;
+    let lines: Vec<String> = dockerfile
+        .split('\n')
+        .map(|line| {
+            if replaced || !line.trim_start().to_uppercase().starts_with("FROM ") {
+                return line.to_string();
+            }
+            let mut tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.iter().skip(1).position(|token| !token.starts_with("--")) {
+                Some(index) => {
+                    replaced = true;
+                    tokens[index + 1] = image;
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    format!("{}{}", indent, tokens.join(" "))
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+// De-duplicates by package name, a pinned version (`git=1:2.34.1-1`) from the spec
+// replaces the unpinned package the language already installs
+fn merge_system_packages(provided: &[String], extra: &[String]) -> Vec<String> {
+    let package_name = |package: &str| package.split('=').next().unwrap_or_default().to_string();
+
+    let mut packages: Vec<String> = Vec::new();
+    for package in provided.iter().chain(extra) {
+        match packages.iter().position(|p| package_name(p) == package_name(package)) {
+            Some(index) if package.contains('=') => packages[index] = package.clone(),
+            Some(_) => {}
+            None => packages.push(package.clone()),
+        }
+    }
+    packages
+}
+
+// One package per line, for a multi-line apt install
+fn package_list(packages: &[String]) -> String {
+    packages.iter().map(|package| format!("    {}", package)).collect::<Vec<_>>().join(" \\\n")
+}
+
+// One ENV line per variable. Values are quoted so spaces survive, $NAME references
+// are still expanded by Docker.
+fn format_env(env: &Option<BTreeMap<String, String>>) -> String {
+    match env {
+        Some(env) if !env.is_empty() => {
+            let lines: Vec<String> = env
+                .iter()
+                .map(|(name, value)| format!("ENV {}=\"{}\"", name, value.replace('\\', "\\\\").replace('"', "\\\"")))
+                .collect();
+            format!("# Environment variables from the spec\n{}\n\n", lines.join("\n"))
+        }
+        _ => String::new(),
+    }
+}
+
+// docker_specs values from the spec, with the language defaults filling the gaps
+fn resolve_docker_specs(config: &TestConfig, language: &LanguageDefinition) -> HashMap<String, String> {
+    let mut specs = language.defaults.clone();
+    if let Some(Value::Object(values)) = config.docker_specs.as_ref().and_then(|s| serde_json::to_value(s).ok()) {
+        for (key, value) in values {
+            match value {
+                Value::String(value) => { specs.insert(key, value); }
+                Value::Number(_) | Value::Bool(_) => { specs.insert(key, value.to_string()); }
+                _ => {}
+            }
+        }
+    }
+    specs
+}
+
+fn docker_spec<'a>(specs: &'a HashMap<String, String>, key: &str) -> &'a str {
+    specs.get(key).map(|s| s.as_str()).unwrap_or_default()
+}
+
+// Custom languages get every docker_specs value as {{key}}, plus {{github_repo_url}},
+// {{commit}}, {{checkout}} (put the repo into /testbed at the commit), {{setup_repo}}
+// (copy in and run setup_repo.sh with the spec's commands), {{system_packages}}
+// (the definition's packages and the spec's extras, space separated) and {{env}} (the
+// spec's environment variables, put in front of {{checkout}} when the template doesn't use it)
+fn render_dockerfile_template(template: &str, specs: &HashMap<String, String>, setup: &RepoSetup) -> String {
+    let setup_repo = "COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
+RUN /bin/bash /root/setup_repo.sh";
+
+    let mut dockerfile = template.to_string();
+    for (key, value) in specs {
+        dockerfile = dockerfile.replace(&format!("{{{{{}}}}}", key), value);
+    }
+    if !dockerfile.contains("{{env}}") {
+        dockerfile = dockerfile.replace("{{checkout}}", "{{env}}{{checkout}}");
+    }
+    dockerfile
+        .replace("{{github_repo_url}}", setup.github_repo_url)
+        .replace("{{commit}}", setup.commit)
+        .replace("{{system_packages}}", &setup.system_packages.join(" "))
+        .replace("{{env}}", &setup.env)
+        .replace("{{checkout}}", &setup.checkout)
+        .replace("{{setup_repo}}", setup_repo)
+}
+
+fn generate_js_dockerfile(
+    specs: &HashMap<String, String>,
+    setup: &RepoSetup
 ) -> String {
-    // Use updated default values for any missing docker specs
-    let ubuntu_version = config.docker_specs.as_ref()
-        .and_then(|specs| specs.ubuntu_version.as_ref())
-        .map(|s| s.as_str())
-        .unwrap_or("22.04");
-    
-    let node_version = config.docker_specs.as_ref()
-        .and_then(|specs| specs.node_version.as_ref())
-        .map(|s| s.as_str())
-        .unwrap_or("20");
-    
-    let pnpm_version = config.docker_specs.as_ref()
-        .and_then(|specs| specs.pnpm_version.as_ref())
-        .map(|s| s.as_str())
-        .unwrap_or("9.5.0");
+    // Missing docker specs were filled with the language defaults
+    let ubuntu_version = docker_spec(specs, "ubuntu_version");
+    let node_version = docker_spec(specs, "node_version");
+    let pnpm_version = docker_spec(specs, "pnpm_version");
+
+    // Base images with Node keep their own version, node_version doesn't apply
+    let node_provided = setup.base_image.map(image_provides_node).unwrap_or(false);
+    let (node_install, node_version_env) = if node_provided {
+        ("# Node comes with the base image\n".to_string(), String::new())
+    } else {
+        (
+            format!("# Install node
+RUN bash -c \"set -eo pipefail && curl -fsSL https://deb.nodesource.com/setup_{}.x | bash -\"
+RUN apt-get update && apt-get install -y nodejs
+", node_version),
+            format!("ENV NODE_VERSION {}\n", node_version),
+        )
+    };
+    // Debian only packages chromium, Ubuntu calls it chromium-browser
+    let chromium = match setup.base_image {
+        Some(image) if !is_ubuntu_image(image) => "chromium",
+        _ => "chromium-browser",
+    };
 
     format!("FROM ubuntu:{}
 
@@ -289,38 +1095,18 @@ ARG DEBIAN_FRONTEND=noninteractive
 ENV TZ=Etc/UTC
 
 RUN apt-get update && apt-get install -y \\
-    build-essential \\
-    curl \\
-    git \\
-    libssl-dev \\
-    software-properties-common \\
-    wget \\
-    gnupg \\
-    jq \\
-    ca-certificates \\
-    dbus \\
-    ffmpeg \\
-    imagemagick \\
-    libcairo2-dev \\
-    libpango1.0-dev \\
-    libjpeg-dev \\
-    libgif-dev \\
-    librsvg2-dev \\
-    pkg-config 
-
-# Install node
-RUN bash -c \"set -eo pipefail && curl -fsSL https://deb.nodesource.com/setup_{}.x | bash -\"
-RUN apt-get update && apt-get install -y nodejs
-RUN node -v && npm -v
+{}
+
+{}RUN node -v && npm -v
 
 # Install pnpm
 RUN npm install --global corepack@latest
 RUN corepack enable pnpm
 
 # Install Chromium for browser testing
-RUN apt-get update && apt-get install -y chromium-browser
-ENV CHROME_BIN=/usr/bin/chromium-browser
-ENV CHROME_PATH=/usr/bin/chromium-browser
+RUN apt-get update && apt-get install -y {}
+ENV CHROME_BIN=/usr/bin/{}
+ENV CHROME_PATH=/usr/bin/{}
 
 RUN adduser --disabled-password --gecos 'dog' nonroot
 
@@ -328,15 +1114,14 @@ RUN adduser --disabled-password --gecos 'dog' nonroot
 ARG DEBIAN_FRONTEND=noninteractive
 ENV TZ=Etc/UTC
 
-RUN printf '%s\\n' \"#!/bin/bash\" \"set -euxo pipefail\" \"\" > /root/setup_env.sh && chmod +x /root/setup_env.sh
+COPY setup_env.sh /root/setup_env.sh
 RUN sed -i -e 's/\\r$//' /root/setup_env.sh
 RUN chmod +x /root/setup_env.sh
 
 ENV NVM_DIR=/usr/local/nvm
 
 # Install Node
-ENV NODE_VERSION {}
-RUN node -v
+{}RUN node -v
 
 # Install Python 3 and Python 2
 RUN apt-get update && apt-get install -y python3 python3-pip python2
@@ -365,13 +1150,10 @@ RUN python2 -V
 RUN yarn -v
 RUN npx -v
 
-WORKDIR /testbed/
-RUN git clone --depth 1 -o origin {} /testbed
-RUN chmod -R 777 /testbed
-RUN git fetch origin {}
-RUN git reset --hard {}
-RUN git remote remove origin
-RUN printf '%s\\n' \"#!/bin/bash\" \"set -euxo pipefail\" {} {} {} \"\" > /root/setup_repo.sh && chmod +x /root/setup_repo.sh
+{}WORKDIR /testbed/
+{}
+COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
 RUN sed -i -e 's/\\r$//' /root/setup_repo.sh
 RUN node -v
 RUN npm -v
@@ -380,135 +1162,357 @@ RUN /bin/bash /root/setup_repo.sh
 WORKDIR /testbed/
 ",
         ubuntu_version,
-        node_version,
-        node_version,
+        package_list(&setup.system_packages),
+        node_install,
+        chromium,
+        chromium,
+        chromium,
+        node_version_env,
         pnpm_version,
-        github_repo_url,
-        commit,
-        commit,
-        pre_install_cmds,
-        install_cmds,
-        build_cmds
+        setup.env,
+        setup.checkout
     )
 }
 
 fn generate_cpp_dockerfile(
-    config: &TestConfig, 
-    github_repo_url: &str, 
-    commit: &str,
-    pre_install_cmds: &str,
-    install_cmds: &str,
-    build_cmds: &str
+    specs: &HashMap<String, String>,
+    setup: &RepoSetup
 ) -> String {
-    // Use updated default values for any missing docker specs
-    let ubuntu_version = config.docker_specs.as_ref()
-        .and_then(|specs| specs.ubuntu_version.as_ref())
-        .map(|s| s.as_str())
-        .unwrap_or("22.04");
+    // Missing docker specs were filled with the language defaults
+    let ubuntu_version = docker_spec(specs, "ubuntu_version");
 
     format!("FROM ubuntu:{}
 
 ARG DEBIAN_FRONTEND=noninteractive
 ENV TZ=Etc/UTC
 
-# Uncomment deb-src lines. Only works on Ubuntu 22.04 and below
-RUN sed -i 's/^# deb-src/deb-src/' /etc/apt/sources.list
+# Uncomment deb-src lines. Only works on Ubuntu 22.04 and below, newer images keep
+# their sources in /etc/apt/sources.list.d instead
+RUN if [ -f /etc/apt/sources.list ]; then sed -i 's/^# deb-src/deb-src/' /etc/apt/sources.list; fi
 
 # Includes dependencies for all C/C++ projects
 RUN apt update && \\
-    apt install -y wget git build-essential libtool automake autoconf tcl bison flex cmake python3 python3-pip python3-venv python-is-python3 && \\
+    apt install -y {} && \\
     rm -rf /var/lib/apt/lists/*
 
 RUN adduser --disabled-password --gecos 'dog' nonroot
 
-WORKDIR /testbed/
-RUN git clone --depth 1 -o origin {} /testbed
-RUN chmod -R 777 /testbed
-RUN git fetch origin {}
-RUN git reset --hard {}
-RUN git remote remove origin
-RUN printf '%s\\n' \"#!/bin/bash\" \"set -euxo pipefail\" {} {} {} \"\" > /root/setup_repo.sh && chmod +x /root/setup_repo.sh
+{}WORKDIR /testbed/
+{}
+COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
 RUN /bin/bash /root/setup_repo.sh
 
 WORKDIR /testbed/
 ",
         ubuntu_version,
-        github_repo_url,
-        commit,
-        commit,
-        pre_install_cmds,
-        install_cmds,
-        build_cmds
+        setup.system_packages.join(" "),
+        setup.env,
+        setup.checkout
     )
 }
 
 fn generate_rust_dockerfile(
-    config: &TestConfig, 
-    github_repo_url: &str, 
-    commit: &str,
-    pre_install_cmds: &str,
-    install_cmds: &str,
-    build_cmds: &str
+    specs: &HashMap<String, String>,
+    setup: &RepoSetup
 ) -> String {
-    // Use updated default values for any missing docker specs
-    let rust_version = config.docker_specs.as_ref()
-        .and_then(|specs| specs.rust_version.as_ref())
-        .map(|s| s.as_str())
-        .unwrap_or("latest");
+    // Missing docker specs were filled with the language defaults
+    let rust_version = docker_spec(specs, "rust_version");
 
     format!("FROM rust:{}
 
 ARG DEBIAN_FRONTEND=noninteractive
 ENV TZ=Etc/UTC
 
-RUN apt update && apt install -y \\
-wget \\
-git \\
-build-essential \\
-&& rm -rf /var/lib/apt/lists/*
-
-RUN adduser --disabled-password --gecos 'dog' nonroot
+{}
 
-WORKDIR /testbed/
-RUN git clone --depth 1 -o origin {} /testbed
-RUN chmod -R 777 /testbed
-RUN git fetch origin {}
-RUN git reset --hard {}
-RUN git remote remove origin
-RUN printf '%s\\n' \"#!/bin/bash\" \"set -euxo pipefail\" {} {} {} \"\" > /root/setup_repo.sh && chmod +x /root/setup_repo.sh
+{}WORKDIR /testbed/
+{}
+COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
 RUN /bin/bash /root/setup_repo.sh
 
 WORKDIR /testbed/
 ",
         rust_version,
-        github_repo_url,
-        commit,
-        commit,
-        pre_install_cmds,
-        install_cmds,
-        build_cmds
+        setup.package_manager.base_layer(&setup.system_packages),
+        setup.env,
+        setup.checkout
     )
 }
 
+fn generate_go_dockerfile(
+    specs: &HashMap<String, String>,
+    setup: &RepoSetup
+) -> String {
+    // Missing docker specs were filled with the language defaults
+    let go_version = docker_spec(specs, "go_version");
+
+    format!("FROM golang:{}
+
+ARG DEBIAN_FRONTEND=noninteractive
+ENV TZ=Etc/UTC
+
+{}
+
+RUN go version
+
+{}WORKDIR /testbed/
+{}
+
+# Pre-warm the module cache so the setup script and the tests don't hit the network
+RUN if [ -f go.mod ]; then go mod download; fi
+
+COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
+RUN /bin/bash /root/setup_repo.sh
+
+WORKDIR /testbed/
+",
+        go_version,
+        setup.package_manager.base_layer(&setup.system_packages),
+        setup.env,
+        setup.checkout
+    )
+}
+
+fn generate_python_dockerfile(
+    specs: &HashMap<String, String>,
+    setup: &RepoSetup
+) -> String {
+    // Missing docker specs were filled with the language defaults
+    let python_version = docker_spec(specs, "python_version");
+
+    // Poetry and uv are opt-in, only install them when a version is given
+    let poetry_install = specs.get("poetry_version")
+        .map(|version| format!("
+# Install poetry into the venv, and make it reuse the venv instead of creating its own
+RUN pip install poetry=={}
+ENV POETRY_VIRTUALENVS_CREATE=false
+", version))
+        .unwrap_or_default();
+
+    let uv_install = specs.get("uv_version")
+        .map(|version| format!("
+# Install uv, installing packages into the active venv
+RUN pip install uv=={}
+ENV UV_PROJECT_ENVIRONMENT=/opt/venv
+", version))
+        .unwrap_or_default();
+
+    format!("FROM python:{}
+
+ARG DEBIAN_FRONTEND=noninteractive
+ENV TZ=Etc/UTC
+
+{}
+
+# Set up a venv, every pip install from the setup script goes into it
+RUN python -m venv /opt/venv
+ENV VIRTUAL_ENV=/opt/venv
+ENV PATH=/opt/venv/bin:$PATH
+RUN pip install --upgrade pip setuptools wheel
+{}{}
+RUN python -V && pip -V
+
+{}WORKDIR /testbed/
+{}
+COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
+RUN /bin/bash /root/setup_repo.sh
+
+WORKDIR /testbed/
+",
+        python_version,
+        setup.package_manager.base_layer(&setup.system_packages),
+        poetry_install,
+        uv_install,
+        setup.env,
+        setup.checkout
+    )
+}
+
+fn generate_java_dockerfile(
+    specs: &HashMap<String, String>,
+    setup: &RepoSetup
+) -> String {
+    // Missing docker specs were filled with the language defaults
+    let jdk_version = docker_spec(specs, "jdk_version");
+    let maven_version = docker_spec(specs, "maven_version");
+    let maven_major = maven_version.split('.').next().unwrap_or("3");
+
+    // Gradle is opt-in, most Gradle projects ship a wrapper
+    let gradle_install = specs.get("gradle_version")
+        .map(|version| format!("
+# Install gradle
+RUN wget -q https://services.gradle.org/distributions/gradle-{0}-bin.zip -O /tmp/gradle.zip \\
+    && unzip -q /tmp/gradle.zip -d /opt/gradle \\
+    && rm /tmp/gradle.zip
+ENV PATH=/opt/gradle/gradle-{0}/bin:$PATH
+RUN gradle --version
+", version))
+        .unwrap_or_default();
+
+    format!("FROM eclipse-temurin:{}-jdk
+
+ARG DEBIAN_FRONTEND=noninteractive
+ENV TZ=Etc/UTC
+
+{}
+
+# Install maven
+RUN wget -q https://archive.apache.org/dist/maven/maven-{}/{}/binaries/apache-maven-{}-bin.tar.gz -O /tmp/maven.tar.gz \\
+    && tar -xzf /tmp/maven.tar.gz -C /opt \\
+    && rm /tmp/maven.tar.gz
+ENV MAVEN_HOME=/opt/apache-maven-{}
+ENV PATH=/opt/apache-maven-{}/bin:$PATH
+RUN java -version && mvn -v
+{}
+{}WORKDIR /testbed/
+{}
+
+# Warm the dependency cache in its own layer, so changing the setup script doesn't download everything again
+RUN if [ -f pom.xml ]; then \\
+        if [ -x mvnw ]; then ./mvnw -B -q dependency:go-offline; else mvn -B -q dependency:go-offline; fi; \\
+    elif [ -x gradlew ]; then ./gradlew --no-daemon -q dependencies; \\
+    elif [ -f build.gradle ] || [ -f build.gradle.kts ]; then gradle --no-daemon -q dependencies; \\
+    fi
+
+COPY setup_repo.sh /root/setup_repo.sh
+RUN chmod +x /root/setup_repo.sh
+RUN /bin/bash /root/setup_repo.sh
+
+WORKDIR /testbed/
+",
+        jdk_version,
+        setup.package_manager.base_layer(&setup.system_packages),
+        maven_major,
+        maven_version,
+        maven_version,
+        maven_version,
+        maven_version,
+        gradle_install,
+        setup.env,
+        setup.checkout
+    )
+}
+
+// Parse a SWE-bench instance, pasted as JSON or as a JSONL file with `line` picking the
+// row, into the fields a tab is prefilled with
+#[tauri::command]
+pub fn import_instance(input: String, line: Option<usize>) -> Result<InstanceRecord, String> {
+    parse_instance(&input, line)
+}
+
+// Every language, with the custom language files that couldn't be loaded
 #[tauri::command]
-pub fn generate_docker_file(input_json: String, github_repo_url: String, commit: String, language: String) -> ValidationResult {
-    match validate_json_config(&input_json, &language) {
-        Ok(config) => {
-            let dockerfile = generate_dockerfile(&config, &github_repo_url, &commit, &language);
+pub fn list_languages() -> LanguageRegistry {
+    REGISTRY.clone()
+}
+
+#[tauri::command]
+pub fn generate_docker_file(
+    input_json: String,
+    github_repo_url: String,
+    commit: String,
+    language: String,
+    input_format: Option<String>,
+    source: Option<RepoSource>,
+) -> ValidationResult {
+    let format = match SpecFormat::from_name(input_format.as_deref().unwrap_or_default()) {
+        Ok(format) => format,
+        Err(error) => {
+            return ValidationResult {
+                success: false,
+                error: Some(error),
+                dockerfile: None,
+                context_files: BTreeMap::new(),
+                issues: Vec::new(),
+                input_format: None,
+            }
+        }
+    };
+    match validate_json_config(&input_json, &language, format) {
+        Ok((config, format)) => {
+            let (dockerfile, context_files) = generate_dockerfile(&config, &github_repo_url, &commit, &language, &source.unwrap_or_default());
             ValidationResult {
                 success: true,
                 error: None,
                 dockerfile: Some(dockerfile),
+                context_files,
+                issues: lint_config(&input_json, format, &config, &language),
+                input_format: Some(format),
             }
         }
         Err(error) => ValidationResult {
             success: false,
-            error: Some(error),
+            error: Some(error.message),
             dockerfile: None,
+            context_files: BTreeMap::new(),
+            // Syntax errors are the only ones without a parsed format
+            input_format: parse_spec(&input_json, format).ok().map(|(_, format)| format),
+            issues: error.issues,
         },
     }
 }
 
+// The spec schema for a language as a standalone draft-07 document, so editors can
+// offer completion and hover docs from the field descriptions. Also written to `path`
+// when one is given.
+#[tauri::command]
+pub fn export_spec_schema(language: String, path: Option<String>) -> Result<Value, String> {
+    let language = get_language(&language);
+    let slug: String = language
+        .name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    let mut schema = get_json_schema(&language.name);
+    if let Some(obj) = schema.as_object_mut() {
+        obj.insert("$schema".to_string(), json!("http://json-schema.org/draft-07/schema#"));
+        obj.insert("$id".to_string(), json!(format!("urn:swebench-debugger:spec:{}", slug)));
+        obj.insert("title".to_string(), json!(format!("SWEBench Debugger {} spec", language.name)));
+    }
+
+    if let Some(path) = path.filter(|path| !path.trim().is_empty()) {
+        fs::write(path.trim(), serde_json::to_string_pretty(&schema).unwrap())
+            .map_err(|e| format!("Failed to save schema: {}", e))?;
+    }
+    Ok(schema)
+}
+
+// Upgrade a spec, in any supported format, to the current spec version. The upgraded
+// spec always comes back as JSON.
+#[tauri::command]
+pub fn migrate_spec(input: String, input_format: Option<String>) -> Result<MigrationResult, String> {
+    let format = SpecFormat::from_name(input_format.as_deref().unwrap_or_default())?;
+    let (mut spec, _) = parse_spec(&input, format)
+        .map_err(|e| format!("Invalid {} syntax: {}", e.format.label(), e.message))?;
+    let (from_version, changes) = migrate_spec_value(&mut spec)?;
+    let spec = serialize_spec(&spec, SpecFormat::Json)
+        .map_err(|e| format!("Failed to serialize the migrated spec as JSON: {}", e))?;
+    Ok(MigrationResult {
+        spec,
+        from_version,
+        to_version: CURRENT_SPEC_VERSION,
+        changes,
+    })
+}
+
+// Convert a spec between JSON, JSON5, YAML and TOML. `from_format` is autodetected
+// when omitted.
+#[tauri::command]
+pub fn convert_spec(input: String, from_format: Option<String>, to_format: String) -> Result<String, String> {
+    let from_format = SpecFormat::from_name(from_format.as_deref().unwrap_or_default())?;
+    let to_format = SpecFormat::from_name(&to_format)?.ok_or("A target format is required")?;
+    let (value, format) = parse_spec(&input, from_format)
+        .map_err(|e| format!("Invalid {} syntax: {}", e.format.label(), e.message))?;
+    serialize_spec(&value, to_format)
+        .map_err(|e| format!("Failed to convert the spec from {} to {}: {}", format.label(), to_format.label(), e))
+}
+
 // Check if Docker is installed and running
 async fn check_docker_available(docker_path: Option<&str>) -> Result<String, String> {
     let docker_cmd = if let Some(path) = docker_path {
@@ -547,13 +1551,61 @@ async fn check_docker_available(docker_path: Option<&str>) -> Result<String, Str
     Ok(docker_cmd)
 }
 
+// Directory in the build context a local repository is exported to
+const LOCAL_REPO_DIR: &str = "repo";
+
+// Commits are passed to git as arguments, where a leading `-` would make them an option
+pub fn check_commit(commit: &str) -> Result<(), String> {
+    if commit.trim().is_empty() {
+        return Err("A commit is required".to_string());
+    }
+    if commit.starts_with('-') {
+        return Err(format!("Invalid commit {}, a commit can't start with '-'", commit));
+    }
+    Ok(())
+}
+
+// Copy the files of `commit` from a local repository into `dest`. A shared clone reads
+// the objects in place, so this works for bare repositories too and leaves the
+// source repository untouched.
+async fn export_local_repo(path: &str, commit: &str, dest: &Path) -> Result<(), String> {
+    check_commit(commit)?;
+    let output = Command::new("git")
+        .args(["clone", "--quiet", "--no-checkout", "--shared", "--config", "core.autocrlf=false", "--", path])
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to read local repository {}: {}", path, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dest)
+        .args(["-c", "advice.detachedHead=false", "checkout", "--quiet", "--detach", commit])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to check out {} from {}: {}", commit, path, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // The clone's .git points at the source repository's objects, which don't exist in the image
+    fs::remove_dir_all(dest.join(".git")).map_err(|e| format!("Failed to clean up the exported repository: {}", e))
+}
+
+// Tauri passes command arguments individually
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn build_docker_image(
     tab_id: String,
     dockerfile_content: String,
+    context_files: BTreeMap<String, String>,
     image_name: String,
     github_repo_url: String,
     commit: String,
+    source: Option<RepoSource>,
     docker_path: String,
     app: AppHandle,
 ) -> Result<(), String> {
@@ -568,19 +1620,50 @@ pub async fn build_docker_image(
         }
     }
 
-    // Create a temporary Dockerfile
-    let mut temp_file = NamedTempFile::new()
-        .map_err(|e| format!("Failed to create temporary file: {}", e))?;
-    temp_file
-        .write_all(dockerfile_content.as_bytes())
+    // Every build gets its own context holding only the Dockerfile and the generated
+    // files, so nothing from the directory the app was started in is sent to the daemon
+    let context_dir = tempfile::Builder::new()
+        .prefix("swebench-build-")
+        .tempdir()
+        .map_err(|e| format!("Failed to create build context: {}", e))?;
+    let dockerfile_path = context_dir.path().join("Dockerfile");
+    fs::write(&dockerfile_path, &dockerfile_content)
         .map_err(|e| format!("Failed to write Dockerfile: {}", e))?;
-    let dockerfile_path = temp_file.path().to_path_buf();
+    for (name, content) in &context_files {
+        if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." || name == "Dockerfile" || name == LOCAL_REPO_DIR {
+            return Err(format!("Invalid build context file name: {}", name));
+        }
+        fs::write(context_dir.path().join(name), content)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+    match &source {
+        Some(RepoSource::Local { path }) => {
+            let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Exporting {} from {}", commit, path)}));
+            export_local_repo(path, &commit, &context_dir.path().join(LOCAL_REPO_DIR)).await?;
+        }
+        Some(RepoSource::Mirror) => {
+            let log = |message: String| {
+                let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": message}));
+            };
+            let mirror_path = update_mirror(&github_repo_url, &commit, log).await?;
+            let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Exporting {} from the mirror", commit)}));
+            export_local_repo(&mirror_path.to_string_lossy(), &commit, &context_dir.path().join(LOCAL_REPO_DIR)).await?;
+        }
+        _ => {}
+    }
+    let (context_file_count, context_size) = directory_size(context_dir.path())
+        .map_err(|e| format!("Failed to read build context: {}", e))?;
 
     let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": "Starting Docker build..."}));
     let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Using Docker: {}", docker_cmd)}));
     let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Building image: {}", image_name)}));
-    let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Repository: {}", github_repo_url)}));
+    let repository = match &source {
+        Some(RepoSource::Local { path }) => path,
+        _ => &github_repo_url,
+    };
+    let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Repository: {}", repository)}));
     let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Commit: {}", commit)}));
+    let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": format!("Build context: {} files, {}", context_file_count, format_size(context_size))}));
     let _ = app.emit("build_log", json!({"tab_id": tab_id, "message": ""}));
 
     let mut cmd = Command::new(&docker_cmd);
@@ -589,7 +1672,7 @@ pub async fn build_docker_image(
         .arg(&dockerfile_path)
         .arg("-t")
         .arg(&image_name)
-        .arg(".")
+        .arg(context_dir.path())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
@@ -608,7 +1691,6 @@ pub async fn build_docker_image(
     let app_clone = app.clone();
     let tab_id_clone = tab_id.clone();
     tauri::async_runtime::spawn(async move {
-        let _temp_file = temp_file;
         let stdout_reader = BufReader::new(stdout);
         let stderr_reader = BufReader::new(stderr);
         let app_clone_stdout = app_clone.clone();
@@ -688,10 +1770,48 @@ pub async fn build_docker_image(
                 let _ = app_clone.emit("build_complete", json!({"tab_id": tab_id_clone, "success": build_complete.success, "error": build_complete.error}));
             }
         }
+
+        if let Err(e) = context_dir.close() {
+            let _ = app_clone.emit("build_log", json!({"tab_id": tab_id_clone, "message": format!("WARNING: Failed to remove the build context: {}", e)}));
+        }
     });
     Ok(())
 }
 
+// Number of files and their total size in bytes, counting subdirectories
+fn directory_size(path: &Path) -> std::io::Result<(u64, u64)> {
+    let mut files = 0;
+    let mut bytes = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let (dir_files, dir_bytes) = directory_size(&entry.path())?;
+            files += dir_files;
+            bytes += dir_bytes;
+        } else if file_type.is_file() {
+            files += 1;
+            bytes += entry.metadata()?.len();
+        }
+    }
+    Ok((files, bytes))
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
 #[tauri::command]
 pub async fn stop_docker_build(tab_id: String) -> Result<(), String> {
     let child = {
@@ -757,6 +1877,11 @@ pub async fn check_docker_image_exists(image_name: String, docker_path: String)
 
 // Get the configuration file path
 fn get_config_path() -> PathBuf {
+    get_app_dir().join("config.json")
+}
+
+// Get the application data directory, ~/.swebench-debugger
+pub fn get_app_dir() -> PathBuf {
     let mut home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
     home.push(".swebench-debugger");
     
@@ -765,7 +1890,7 @@ fn get_config_path() -> PathBuf {
         let _ = fs::create_dir_all(&home);
     }
     
-    home.join("config.json")
+    home
 }
 
 #[tauri::command]
@@ -784,140 +1909,250 @@ pub fn save_config(key: String, value: String) -> Result<(), String> {
     }
     fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap())
         .map_err(|e| format!("Failed to save configuration: {}", e))?;
+    *CONFIG.lock().unwrap() = Some(config);
     Ok(())
 }
 
 #[tauri::command]
 pub fn load_config(key: String) -> Result<String, String> {
-    let config_path = get_config_path();
-    if !config_path.exists() {
-        return Ok(String::new());
+    let mut cached = CONFIG.lock().unwrap();
+    if cached.is_none() {
+        let config_path = get_config_path();
+        if !config_path.exists() {
+            return Ok(String::new());
+        }
+        let content = fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read configuration: {}", e))?;
+        let config: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse configuration: {}", e))?;
+        *cached = Some(config);
     }
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read configuration: {}", e))?;
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse configuration: {}", e))?;
-    Ok(config.get(&key).and_then(|v| v.as_str()).unwrap_or("").to_string())
+    Ok(cached.as_ref().and_then(|config| config.get(&key)).and_then(|v| v.as_str()).unwrap_or("").to_string())
 }
 
-#[tauri::command]
-pub async fn run_docker_test(
-    tab_id: String,
-    image_name: String,
-    test_cmd: String,
-    test_file_paths: String,
-    docker_path: String,
-    app: AppHandle,
-) -> Result<(), String> {
-    let docker_cmd = check_docker_available(if docker_path.is_empty() { None } else { Some(&docker_path) }).await?;
-    {
-        let processes = TEST_PROCESSES.lock().unwrap();
-        if processes.contains_key(&tab_id) {
-            return Err("A Docker test is already running for this tab".to_string());
-        }
+// What a finished test container left behind
+struct TestRun {
+    // None when the run was stopped
+    status: Option<std::process::ExitStatus>,
+    // stdout and stderr together, in the order the lines arrived
+    output: String,
+    patch_failure: Option<PatchFailure>,
+}
+
+// The non-empty patches out of `patches`, each logged as it's picked up
+fn patches_to_apply<'a>(patches: &[(&'a str, &'a Option<String>)], tab_id: &str, app: &AppHandle) -> Vec<(&'a str, &'a str)> {
+    let patches: Vec<(&str, &str)> = patches
+        .iter()
+        .filter_map(|(name, diff)| diff.as_deref().filter(|diff| !diff.trim().is_empty()).map(|diff| (*name, diff)))
+        .collect();
+    for (name, diff) in &patches {
+        let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Applying {} ({} lines)", name, diff.lines().count())}));
     }
-    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": "Starting Docker test run..."}));
-    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Using Docker: {}", docker_cmd)}));
-    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Image: {}", image_name)}));
-    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Test command: {}", test_cmd)}));
-    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Test files: {}", test_file_paths)}));
-    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": ""}));
-    let full_test_cmd = if test_file_paths.trim().is_empty() {
-        test_cmd
+    patches
+}
+
+// Start the tests in a container registered under the tab in TEST_PROCESSES, so
+// stop_docker_test can kill it. The patches are applied in /testbed first, in order.
+fn start_test_container(
+    docker_cmd: &str,
+    image_name: &str,
+    full_test_cmd: &str,
+    patches: &[(&str, &str)],
+    tab_id: &str,
+) -> Result<(ChildStdout, ChildStderr), String> {
+    let names: Vec<&str> = patches.iter().map(|(name, _)| *name).collect();
+    let diffs: Vec<&str> = patches.iter().map(|(_, diff)| *diff).collect();
+    let script = if patches.is_empty() {
+        full_test_cmd.to_string()
     } else {
-        format!("{} {}", test_cmd, test_file_paths)
+        format!("{}{}", apply_script(&names), full_test_cmd)
     };
-    let mut cmd = Command::new(&docker_cmd);
+
+    let mut cmd = Command::new(docker_cmd);
     cmd.arg("run")
         .arg("--rm")
-        .arg(&image_name)
+        .arg("-i")
+        .arg(image_name)
         .arg("bash")
         .arg("-c")
-        .arg(&full_test_cmd)
+        .arg(&script)
+        .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
     let mut child = cmd
         .spawn()
         .map_err(|e| format!("Failed to start Docker test: {}", e))?;
+    let mut stdin = child.stdin.take().ok_or("Failed to capture stdin")?;
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+
+    // The patches go in over stdin, closing it afterwards so nothing in the tests waits on it
+    let encoded_patches = encode_patches(&diffs);
+    tauri::async_runtime::spawn(async move {
+        let _ = stdin.write_all(encoded_patches.as_bytes()).await;
+    });
     {
         let mut processes = TEST_PROCESSES.lock().unwrap();
-        processes.insert(tab_id.clone(), child);
+        processes.insert(tab_id.to_string(), child);
     }
-    let app_clone = app.clone();
-    let tab_id_clone = tab_id.clone();
-    tauri::async_runtime::spawn(async move {
-        let stdout_reader = BufReader::new(stdout);
-        let stderr_reader = BufReader::new(stderr);
-        let app_clone_stdout = app_clone.clone();
-        let tab_id_stdout = tab_id_clone.clone();
-        let stdout_task = tauri::async_runtime::spawn(async move {
-            let mut lines = stdout_reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                let _ = app_clone_stdout.emit("test_log", json!({"tab_id": tab_id_stdout, "message": line}));
-            }
-        });
-        let app_clone_stderr = app_clone.clone();
-        let tab_id_stderr = tab_id_clone.clone();
-        let stderr_task = tauri::async_runtime::spawn(async move {
-            let mut lines = stderr_reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
+    Ok((stdout, stderr))
+}
+
+// Stream a started test container's output to the tab and wait for it to exit
+async fn finish_test_run(stdout: ChildStdout, stderr: ChildStderr, tab_id: &str, app: &AppHandle) -> Result<TestRun, String> {
+    let output = Arc::new(Mutex::new(String::new()));
+    let stdout_reader = BufReader::new(stdout);
+    let stderr_reader = BufReader::new(stderr);
+    let app_clone_stdout = app.clone();
+    let tab_id_stdout = tab_id.to_string();
+    let output_stdout = output.clone();
+    let stdout_task = tauri::async_runtime::spawn(async move {
+        let mut lines = stdout_reader.lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            output_stdout.lock().unwrap().push_str(&format!("{}\n", line));
+            let _ = app_clone_stdout.emit("test_log", json!({"tab_id": tab_id_stdout, "message": line}));
+        }
+    });
+    let app_clone_stderr = app.clone();
+    let tab_id_stderr = tab_id.to_string();
+    let output_stderr = output.clone();
+    let stderr_task = tauri::async_runtime::spawn(async move {
+        let mut lines = stderr_reader.lines();
+        let mut patch_failures = PatchFailureDetector::default();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if patch_failures.feed(&line) {
+                output_stderr.lock().unwrap().push_str(&format!("{}\n", line));
                 let _ = app_clone_stderr.emit("test_log", json!({"tab_id": tab_id_stderr, "message": format!("STDERR: {}", line)}));
             }
-        });
-        let mut status_code = None;
-        let mut process_result = Ok(());
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            let mut should_break = false;
-            let mut processes = TEST_PROCESSES.lock().unwrap();
-            if let Some(child_process) = processes.get_mut(&tab_id_clone) {
-                match child_process.try_wait() {
-                    Ok(Some(status)) => {
-                        status_code = Some(status);
-                        should_break = true;
-                    }
-                    Ok(None) => {}
-                    Err(e) => {
-                        process_result = Err(format!("Process error: {}", e));
-                        should_break = true;
-                    }
+        }
+        patch_failures.failure()
+    });
+    let mut status_code = None;
+    let mut process_result = Ok(());
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let mut should_break = false;
+        let mut processes = TEST_PROCESSES.lock().unwrap();
+        if let Some(child_process) = processes.get_mut(tab_id) {
+            match child_process.try_wait() {
+                Ok(Some(status)) => {
+                    status_code = Some(status);
+                    should_break = true;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    process_result = Err(format!("Process error: {}", e));
+                    should_break = true;
                 }
-            } else {
-                should_break = true;
-            }
-            drop(processes);
-            if should_break {
-                break;
             }
+        } else {
+            should_break = true;
         }
-        let _ = tokio::join!(stdout_task, stderr_task);
-        {
-            let mut processes = TEST_PROCESSES.lock().unwrap();
-            processes.remove(&tab_id_clone);
+        drop(processes);
+        if should_break {
+            break;
         }
-        match process_result {
-            Ok(()) => {
-                if let Some(status) = status_code {
-                    let success = status.success();
-                    let test_complete = TestCompleteEvent {
-                        success,
-                        error: if success { None } else { Some("Test run failed".to_string()) },
-                    };
-                    let _ = app_clone.emit("test_complete", json!({"tab_id": tab_id_clone, "success": test_complete.success, "error": test_complete.error}));
-                    if !success {
-                        let _ = app_clone.emit("test_log", json!({"tab_id": tab_id_clone, "message": format!("ERROR: Docker test run failed with exit code: {}", status.code().unwrap_or(-1))}));
-                    }
-                } else {
-                    let test_complete = TestCompleteEvent {
-                        success: false,
-                        error: Some("Test was stopped".to_string()),
-                    };
-                    let _ = app_clone.emit("test_complete", json!({"tab_id": tab_id_clone, "success": test_complete.success, "error": test_complete.error}));
-                    let _ = app_clone.emit("test_log", json!({"tab_id": tab_id_clone, "message": "Test stopped by user"}));
+    }
+    let (_, patch_failure) = tokio::join!(stdout_task, stderr_task);
+    {
+        let mut processes = TEST_PROCESSES.lock().unwrap();
+        processes.remove(tab_id);
+    }
+    process_result?;
+    let output = output.lock().unwrap().clone();
+    Ok(TestRun {
+        status: status_code,
+        output,
+        patch_failure: patch_failure.ok().flatten(),
+    })
+}
+
+fn full_test_command(test_cmd: String, test_file_paths: &str) -> String {
+    if test_file_paths.trim().is_empty() {
+        test_cmd
+    } else {
+        format!("{} {}", test_cmd, test_file_paths)
+    }
+}
+
+fn log_test_run_start(docker_cmd: &str, image_name: &str, test_cmd: &str, test_file_paths: &str, tab_id: &str, app: &AppHandle) {
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Using Docker: {}", docker_cmd)}));
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Image: {}", image_name)}));
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Test command: {}", test_cmd)}));
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Test files: {}", test_file_paths)}));
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": ""}));
+}
+
+fn ensure_no_test_running(tab_id: &str) -> Result<(), String> {
+    let processes = TEST_PROCESSES.lock().unwrap();
+    if processes.contains_key(tab_id) {
+        return Err("A Docker test is already running for this tab".to_string());
+    }
+    Ok(())
+}
+
+// `patch` and `test_patch` are applied in /testbed, in that order, before the tests run
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn run_docker_test(
+    tab_id: String,
+    image_name: String,
+    test_cmd: String,
+    test_file_paths: String,
+    patch: Option<String>,
+    test_patch: Option<String>,
+    log_parser_name: Option<String>,
+    log_parser: Option<RegexParserSpec>,
+    log_parser_script: Option<String>,
+    docker_path: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    let docker_cmd = check_docker_available(if docker_path.is_empty() { None } else { Some(&docker_path) }).await?;
+    ensure_no_test_running(&tab_id)?;
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": "Starting Docker test run..."}));
+    log_test_run_start(&docker_cmd, &image_name, &test_cmd, &test_file_paths, &tab_id, &app);
+    let full_test_cmd = full_test_command(test_cmd, &test_file_paths);
+    let patches = patches_to_apply(&[("patch", &patch), ("test_patch", &test_patch)], &tab_id, &app);
+    let (stdout, stderr) = start_test_container(&docker_cmd, &image_name, &full_test_cmd, &patches, &tab_id)?;
+    let log_parser = log_parser_name
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let script = parser_script(log_parser_script);
+            LogParser::new(&name, log_parser.as_ref(), script.as_deref()).map(|parser| (name, parser))
+        });
+
+    let app_clone = app.clone();
+    let tab_id_clone = tab_id.clone();
+    tauri::async_runtime::spawn(async move {
+        match finish_test_run(stdout, stderr, &tab_id_clone, &app_clone).await {
+            // A patch that doesn't apply means the tests never ran
+            Ok(TestRun { patch_failure: Some(failure), .. }) => {
+                let _ = app_clone.emit("test_complete", json!({"tab_id": tab_id_clone, "success": false, "error": failure.describe(), "patch_failure": {"patch": failure.patch, "hunk": failure.hunk, "message": failure.message}}));
+            }
+            Ok(TestRun { status: Some(status), output, .. }) => {
+                let results = match log_parser {
+                    Some(parser) => emit_test_results(parser, &output, &tab_id_clone, &app_clone).await,
+                    None => None,
+                };
+                let summary = results.as_deref().map(summarize);
+                let success = status.success();
+                let test_complete = TestCompleteEvent {
+                    success,
+                    error: if success { None } else { Some("Test run failed".to_string()) },
+                };
+                let _ = app_clone.emit("test_complete", json!({"tab_id": tab_id_clone, "success": test_complete.success, "error": test_complete.error, "results": results, "summary": summary}));
+                if !success {
+                    let _ = app_clone.emit("test_log", json!({"tab_id": tab_id_clone, "message": format!("ERROR: Docker test run failed with exit code: {}", status.code().unwrap_or(-1))}));
                 }
             }
+            Ok(TestRun { status: None, .. }) => {
+                let test_complete = TestCompleteEvent {
+                    success: false,
+                    error: Some("Test was stopped".to_string()),
+                };
+                let _ = app_clone.emit("test_complete", json!({"tab_id": tab_id_clone, "success": test_complete.success, "error": test_complete.error}));
+                let _ = app_clone.emit("test_log", json!({"tab_id": tab_id_clone, "message": "Test stopped by user"}));
+            }
             Err(e) => {
                 let _ = app_clone.emit("test_log", json!({"tab_id": tab_id_clone, "message": format!("ERROR: {}", e)}));
                 let test_complete = TestCompleteEvent {
@@ -931,6 +2166,146 @@ pub async fn run_docker_test(
     Ok(())
 }
 
+// The spec's parser script, or the one in the settings
+fn parser_script(spec_script: Option<String>) -> Option<String> {
+    spec_script
+        .filter(|script| !script.trim().is_empty())
+        .or_else(|| load_config("log_parser_script".to_string()).ok().filter(|script| !script.trim().is_empty()))
+}
+
+// Per-test results for a finished run, in a `test_results` event and returned for
+// `test_complete`. Parsers the app doesn't have only get a note in the log, a parser
+// script that fails gets an error.
+async fn emit_test_results(
+    parser: Result<(String, LogParser), String>,
+    output: &str,
+    tab_id: &str,
+    app: &AppHandle,
+) -> Option<Vec<TestResult>> {
+    match parser {
+        Ok((parser_name, parser)) => match parser.parse(output).await {
+            Ok(results) => {
+                let summary = summarize(&results);
+                let _ = app.emit("test_results", json!({"tab_id": tab_id, "parser": parser_name, "results": results, "summary": summary}));
+                Some(results)
+            }
+            Err(e) => {
+                let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("ERROR: {}", e)}));
+                None
+            }
+        },
+        Err(e) => {
+            let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("{}, no per-test results", e)}));
+            None
+        }
+    }
+}
+
+// One verification run, the parsed results or why there are none
+async fn verification_run(
+    docker_cmd: &str,
+    image_name: &str,
+    full_test_cmd: &str,
+    patches: &[(&str, &Option<String>)],
+    log_parser: &LogParser,
+    tab_id: &str,
+    app: &AppHandle,
+) -> Result<Result<Vec<TestResult>, PatchFailure>, String> {
+    let patches = patches_to_apply(patches, tab_id, app);
+    let (stdout, stderr) = start_test_container(docker_cmd, image_name, full_test_cmd, &patches, tab_id)?;
+    let run = finish_test_run(stdout, stderr, tab_id, app).await?;
+    if let Some(failure) = run.patch_failure {
+        return Ok(Err(failure));
+    }
+    if run.status.is_none() {
+        return Err("Test was stopped".to_string());
+    }
+    let results = log_parser.parse(&run.output).await?;
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("Parsed {} test results", results.len())}));
+    Ok(Ok(results))
+}
+
+// Check an instance the way the SWE-bench harness would: run the tests with the
+// test_patch alone, then with the gold patch as well, and compare the parsed results
+// against FAIL_TO_PASS and PASS_TO_PASS. The outcome arrives in a `verify_complete` event.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn verify_instance(
+    tab_id: String,
+    image_name: String,
+    test_cmd: String,
+    test_file_paths: String,
+    log_parser_name: String,
+    log_parser: Option<RegexParserSpec>,
+    log_parser_script: Option<String>,
+    instance_id: Option<String>,
+    patch: String,
+    test_patch: String,
+    fail_to_pass: Vec<String>,
+    pass_to_pass: Vec<String>,
+    docker_path: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    if patch.trim().is_empty() {
+        return Err("Verifying an instance needs its gold patch".to_string());
+    }
+    if fail_to_pass.is_empty() && pass_to_pass.is_empty() {
+        return Err("The instance has no FAIL_TO_PASS or PASS_TO_PASS tests to verify".to_string());
+    }
+    let script = parser_script(log_parser_script);
+    let log_parser = LogParser::new(&log_parser_name, log_parser.as_ref(), script.as_deref())
+        .map_err(|e| format!("{}, so the tests can't be verified", e))?;
+    let docker_cmd = check_docker_available(if docker_path.is_empty() { None } else { Some(&docker_path) }).await?;
+    ensure_no_test_running(&tab_id)?;
+    let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": "Starting instance verification..."}));
+    log_test_run_start(&docker_cmd, &image_name, &test_cmd, &test_file_paths, &tab_id, &app);
+    let full_test_cmd = full_test_command(test_cmd, &test_file_paths);
+    let instance_id = instance_id.filter(|id| !id.trim().is_empty()).unwrap_or_else(|| image_name.clone());
+
+    tauri::async_runtime::spawn(async move {
+        let complete = |success: bool, error: Option<String>, report: Option<Value>, verdicts: Vec<TestVerdict>| {
+            if let Some(error) = &error {
+                let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!("ERROR: {}", error)}));
+            }
+            let _ = app.emit("verify_complete", json!({"tab_id": tab_id, "success": success, "error": error, "report": report, "verdicts": verdicts}));
+        };
+
+        let test_patch = Some(test_patch);
+        let patch = Some(patch);
+        let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": "=== Run 1 of 2: test_patch only ==="}));
+        let before = match verification_run(&docker_cmd, &image_name, &full_test_cmd, &[("test_patch", &test_patch)], &log_parser, &tab_id, &app).await {
+            Ok(Ok(results)) => results,
+            Ok(Err(failure)) => return complete(false, Some(failure.describe()), None, Vec::new()),
+            Err(e) => return complete(false, Some(e), None, Vec::new()),
+        };
+
+        let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": ""}));
+        let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": "=== Run 2 of 2: test_patch and the gold patch ==="}));
+        let after = match verification_run(&docker_cmd, &image_name, &full_test_cmd, &[("patch", &patch), ("test_patch", &test_patch)], &log_parser, &tab_id, &app).await {
+            Ok(Ok(results)) => results,
+            Ok(Err(failure)) => return complete(false, Some(failure.describe()), Some(patch_failed_report(&instance_id)), Vec::new()),
+            Err(e) => return complete(false, Some(e), None, Vec::new()),
+        };
+
+        let verdicts = verdicts(&fail_to_pass, &pass_to_pass, &before, &after);
+        let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": ""}));
+        for verdict in &verdicts {
+            let _ = app.emit("test_log", json!({"tab_id": tab_id, "message": format!(
+                "{} {} {}: {:?} -> {:?}",
+                if verdict.success { "OK  " } else { "FAIL" },
+                verdict.category,
+                verdict.name,
+                verdict.before,
+                verdict.after
+            )}));
+        }
+        let failed = verdicts.iter().filter(|verdict| !verdict.success).count();
+        let error = if failed == 0 { None } else { Some(format!("{} of {} tests did not verify", failed, verdicts.len())) };
+        complete(failed == 0, error, Some(report(&instance_id, &verdicts)), verdicts);
+    });
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn stop_docker_test(tab_id: String) -> Result<(), String> {
     let child = {
@@ -943,4 +2318,579 @@ pub async fn stop_docker_test(tab_id: String) -> Result<(), String> {
     } else {
         Err("No test process is currently running for this tab".to_string())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::run_git;
+
+    fn config(spec: Value) -> TestConfig {
+        serde_json::from_value(spec).unwrap()
+    }
+
+    // Quotes, `$`, backticks and backslashes that an escaping layer would mangle
+    const TRICKY_COMMANDS: &[&str] = &[
+        r#"sed -i "s/\"a\"/b/" x"#,
+        r#"echo "$HOME" '$HOME' \$PATH"#,
+        "export VERSION=`git describe --tags` && echo \"${VERSION%%-*}\"",
+        r#"printf '%s\n' "a\tb" 'c\\d' > out.txt"#,
+        r#"python -c 'print("100%")'"#,
+    ];
+
+    #[test]
+    fn setup_commands_reach_the_script_byte_for_byte() {
+        let spec = json!({
+            "test_cmd": "npm test",
+            "log_parser_name": "agentic",
+            "pre_install": [TRICKY_COMMANDS[0]],
+            "install": TRICKY_COMMANDS[1..4],
+            "build": [TRICKY_COMMANDS[4]],
+        });
+        for language in ["Javascript", "Python", "Go", "Rust", "Java", "C/CPP"] {
+            let result = generate_docker_file(
+                spec.to_string(),
+                "https://github.com/owner/repo".to_string(),
+                "abc123".to_string(),
+                language.to_string(),
+                None,
+                None,
+            );
+            assert!(result.success, "{}: {:?}", language, result.error);
+
+            let script = &result.context_files["setup_repo.sh"];
+            let expected: String = TRICKY_COMMANDS.iter().map(|command| format!("{}\n", command)).collect();
+            assert_eq!(script, &format!("#!/bin/bash\nset -euxo pipefail\n{}", expected), "{}", language);
+
+            let dockerfile = result.dockerfile.unwrap();
+            assert!(dockerfile.contains("COPY setup_repo.sh /root/setup_repo.sh"), "{}", language);
+            assert!(!dockerfile.contains("printf"), "{}", language);
+            for command in TRICKY_COMMANDS {
+                assert!(!dockerfile.contains(command), "{} inlines {}", language, command);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn exports_a_commit_from_a_bare_repository() {
+        let temp = tempfile::tempdir().unwrap();
+        let work = temp.path().join("work");
+        fs::create_dir(&work).unwrap();
+        run_git(&work, &["init", "-q"]);
+        fs::write(work.join("a.txt"), "first\n").unwrap();
+        run_git(&work, &["add", "a.txt"]);
+        run_git(&work, &["commit", "-q", "-m", "first"]);
+        let first = run_git(&work, &["rev-parse", "HEAD"]);
+        fs::write(work.join("a.txt"), "second\n").unwrap();
+        fs::write(work.join("b.txt"), "added later\n").unwrap();
+        run_git(&work, &["add", "a.txt", "b.txt"]);
+        run_git(&work, &["commit", "-q", "-m", "second"]);
+        let bare = temp.path().join("bare.git");
+        run_git(temp.path(), &["clone", "-q", "--bare", "work", "bare.git"]);
+
+        let dest = temp.path().join("export");
+        export_local_repo(&bare.to_string_lossy(), &first, &dest).await.unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "first\n");
+        assert!(!dest.join("b.txt").exists());
+        assert!(!dest.join(".git").exists());
+        // The source repository is left as it was
+        assert_eq!(run_git(&bare, &["rev-parse", "HEAD"]), run_git(&work, &["rev-parse", "HEAD"]));
+
+        let error = export_local_repo(&bare.to_string_lossy(), "--upload-pack=touch pwned", &temp.path().join("option"))
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Invalid commit --upload-pack=touch pwned, a commit can't start with '-'");
+        assert!(!temp.path().join("option").exists());
+    }
+
+    #[test]
+    fn edit_distance_counts_swapped_neighbours_once() {
+        assert_eq!(edit_distance("jest", "jest"), 0);
+        assert_eq!(edit_distance("jset", "jest"), 1);
+        assert_eq!(edit_distance("node_verison", "node_version"), 1);
+        assert_eq!(edit_distance("mocah", "mocha"), 1);
+        assert_eq!(edit_distance("", "tap"), 3);
+        assert_eq!(edit_distance("karma", "karm"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn closest_match_only_suggests_likely_typos() {
+        let parsers = ["jest", "mocha", "vitest", "karma", "tap", "agentic"];
+        assert_eq!(closest_match("jset", parsers), Some("jest"));
+        assert_eq!(closest_match("JEST", parsers), Some("jest"));
+        assert_eq!(closest_match("vitset", parsers), Some("vitest"));
+        assert_eq!(closest_match("node_verison", ["ubuntu_version", "node_version", "pnpm_version"]), Some("node_version"));
+        // A third of the input may differ, at least one character
+        assert_eq!(closest_match("tp", parsers), Some("tap"));
+        assert_eq!(closest_match("unittest", parsers), None);
+        assert_eq!(closest_match("cargo", parsers), None);
+        assert_eq!(closest_match("unrelated_key", ["ubuntu_version", "node_version", "pnpm_version"]), None);
+        assert_eq!(closest_match("jest", []), None);
+    }
+
+    #[test]
+    fn closest_match_prefers_the_nearest_then_the_first_candidate() {
+        assert_eq!(closest_match("gotest-jsn", ["gotest", "gotest-json"]), Some("gotest-json"));
+        // "tap" and "tar" are both one edit away from "tav"
+        assert_eq!(closest_match("tav", ["tap", "tar"]), Some("tap"));
+        assert_eq!(closest_match("tav", ["tar", "tap"]), Some("tar"));
+    }
+
+    #[test]
+    fn unknown_values_suggest_a_close_match() {
+        let validate = |spec: Value| generate_docker_file(
+            spec.to_string(),
+            "https://github.com/owner/repo".to_string(),
+            "abc123".to_string(),
+            "Javascript".to_string(),
+            None,
+            None,
+        );
+
+        let result = validate(json!({"test_cmd": "npm test", "log_parser_name": "jest", "docker_specs": {"node_verison": "20"}}));
+        assert!(!result.success);
+        assert!(
+            result.error.as_deref().unwrap().contains("Did you mean `node_version` instead of `node_verison`?"),
+            "{:?}", result.error
+        );
+        assert_eq!(result.issues[0].pointer, "/docker_specs/node_verison");
+        assert_eq!(result.issues[0].suggestion.as_deref(), Some("Did you mean `node_version`?"));
+
+        let result = validate(json!({"test_cmd": "npm test", "log_parser_name": "jset"}));
+        assert!(result.error.as_deref().unwrap().ends_with("Did you mean `jest`?"), "{:?}", result.error);
+        assert_eq!(result.issues[0].pointer, "/log_parser_name");
+        assert_eq!(result.issues[0].suggestion.as_deref(), Some("Did you mean `jest`?"));
+
+        let result = validate(json!({"test_cmd": "npm test", "log_parser_name": "unittest"}));
+        assert!(!result.error.as_deref().unwrap().contains("Did you mean"), "{:?}", result.error);
+        assert_eq!(result.issues[0].suggestion, None);
+    }
+
+    #[test]
+    fn convert_spec_round_trips_every_format() {
+        let spec = json!({
+            "spec_version": 2,
+            "test_cmd": "npx jest --ci",
+            "log_parser_name": "jest",
+            "install": ["npm ci", "echo \"it's $HOME\""],
+            "env": {"NODE_OPTIONS": "--max-old-space-size=4096"},
+            "docker_specs": {"node_version": "20", "system_packages": ["libpq-dev"]},
+        });
+        for format in ["json", "json5", "yaml", "toml"] {
+            let converted = convert_spec(spec.to_string(), None, format.to_string()).unwrap();
+            let back = convert_spec(converted.clone(), Some(format.to_string()), "json".to_string()).unwrap();
+            assert_eq!(serde_json::from_str::<Value>(&back).unwrap(), spec, "{}:\n{}", format, converted);
+        }
+        // Autodetection reads each of them back, JSON5 output is plain JSON
+        for (format, detected) in [("yaml", SpecFormat::Yaml), ("toml", SpecFormat::Toml), ("json5", SpecFormat::Json)] {
+            let converted = convert_spec(spec.to_string(), None, format.to_string()).unwrap();
+            assert_eq!(parse_spec(&converted, None).unwrap(), (spec.clone(), detected), "{}", format);
+        }
+
+        assert_eq!(convert_spec(spec.to_string(), None, "xml".to_string()).unwrap_err(), "Unknown spec format: xml");
+        assert_eq!(convert_spec(spec.to_string(), None, "auto".to_string()).unwrap_err(), "A target format is required");
+        let error = convert_spec(json!({"test_cmd": "npm test", "install": null}).to_string(), None, "toml".to_string());
+        assert_eq!(error.unwrap(), "test_cmd = \"npm test\"\n");
+    }
+
+    #[test]
+    fn issues_in_migrated_values_point_at_the_source() {
+        let input = r#"{
+  "test_cmd": "npm test",
+  "log_parser_name": "jest",
+  "pre_install": "npm ci",
+  "install": "npm ci",
+  "docker_specs": { "node_version": 20, "pnpm_version": "9" }
+}"#;
+        let result = generate_docker_file(
+            input.to_string(),
+            "https://github.com/owner/repo".to_string(),
+            "abc123".to_string(),
+            "Javascript".to_string(),
+            None,
+            None,
+        );
+        assert!(result.success, "{:?}", result.error);
+        // The warning is about /install/0 of the migrated spec, which is the string on line 5
+        assert_eq!(result.issues.len(), 1, "{:?}", result.issues);
+        let issue = &result.issues[0];
+        assert_eq!(issue.rule.as_deref(), Some("duplicate-install-command"));
+        assert_eq!(issue.pointer, "/install");
+        assert_eq!((issue.line, issue.column, issue.end_line, issue.end_column), (5, 14, 5, 22));
+
+        // Errors in values the migration didn't touch keep their own position
+        let input = input.replace("\"pnpm_version\": \"9\"", "\"pnpm_verison\": \"9\"");
+        let result = generate_docker_file(
+            input,
+            "https://github.com/owner/repo".to_string(),
+            "abc123".to_string(),
+            "Javascript".to_string(),
+            None,
+            None,
+        );
+        assert!(!result.success);
+        let issue = &result.issues[0];
+        assert_eq!(issue.pointer, "/docker_specs/pnpm_verison");
+        assert_eq!((issue.line, issue.column), (6, 41));
+    }
+
+    #[test]
+    fn env_values_are_quoted_and_escaped() {
+        let env: BTreeMap<String, String> = [
+            ("GREETING", r#"say "hi" to $USER"#),
+            ("JAVA_OPTS", "-Xmx2g -Dfile.encoding=UTF-8"),
+            ("WINDOWS_PATH", r"C:\tools\"),
+            ("EMPTY", ""),
+        ]
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        assert_eq!(
+            format_env(&Some(env)),
+            r#"# Environment variables from the spec
+ENV EMPTY=""
+ENV GREETING="say \"hi\" to $USER"
+ENV JAVA_OPTS="-Xmx2g -Dfile.encoding=UTF-8"
+ENV WINDOWS_PATH="C:\\tools\\"
+
+"#
+        );
+        assert_eq!(format_env(&Some(BTreeMap::new())), "");
+        assert_eq!(format_env(&None), "");
+    }
+
+    #[test]
+    fn env_lines_come_before_the_checkout() {
+        let spec = json!({
+            "test_cmd": "make test",
+            "log_parser_name": "agentic",
+            "env": {"CI": "true", "GREETING": "say \"hi\" to $USER"},
+        });
+        for language in ["Javascript", "Python", "Go", "Rust", "Java", "C/CPP"] {
+            let (dockerfile, _) = generate_dockerfile(&config(spec.clone()), "https://github.com/owner/repo", "abc123", language, &RepoSource::Clone);
+            let expected = "# Environment variables from the spec
+ENV CI=\"true\"
+ENV GREETING=\"say \\\"hi\\\" to $USER\"
+
+WORKDIR /testbed/
+RUN git clone --depth 1 -o origin https://github.com/owner/repo /testbed
+";
+            assert!(dockerfile.contains(expected), "{}:\n{}", language, dockerfile);
+            assert_eq!(dockerfile.matches("ENV CI=").count(), 1, "{}", language);
+        }
+    }
+
+    #[test]
+    fn pinned_packages_replace_unpinned_ones() {
+        let packages = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            merge_system_packages(&packages(&["wget", "git", "build-essential"]), &packages(&["git=1:2.34.1-1ubuntu1", "jq", "wget", "jq=1.6-2.1"])),
+            packages(&["wget", "git=1:2.34.1-1ubuntu1", "build-essential", "jq=1.6-2.1"])
+        );
+        // An unpinned package doesn't undo a pin
+        assert_eq!(merge_system_packages(&packages(&["git=1:2.34.1-1"]), &packages(&["git"])), packages(&["git=1:2.34.1-1"]));
+        assert_eq!(merge_system_packages(&packages(&["git"]), &[]), packages(&["git"]));
+    }
+
+    #[test]
+    fn alpine_images_get_the_spec_packages_under_apk_names() {
+        let spec = json!({
+            "test_cmd": "cargo test",
+            "log_parser_name": "cargo",
+            "docker_specs": {
+                "base_image": "rust:1.75-alpine3.19",
+                "system_packages": ["libssl-dev", "git=1:2.39.2-1.1", "python3-venv", "curl=8.5.0-r0", "protobuf-dev"]
+            }
+        });
+        let (dockerfile, _) = generate_dockerfile(&config(spec.clone()), "https://github.com/owner/repo", "abc123", "Rust", &RepoSource::Clone);
+        assert!(dockerfile.starts_with("FROM rust:1.75-alpine3.19\n"), "{}", dockerfile);
+        assert!(dockerfile.contains("RUN apk add --no-cache \\
+    bash \\
+    wget \\
+    git \\
+    build-base \\
+    openssl-dev \\
+    curl=8.5.0-r0 \\
+    protobuf-dev
+
+RUN adduser -D -g 'dog' nonroot"), "{}", dockerfile);
+
+        let warnings = lint_base_image_compatibility(&config(spec), get_language("Rust"));
+        let warnings: Vec<(&str, &str)> = warnings.iter().map(|w| (w.pointer.as_str(), w.message.as_str())).collect();
+        assert_eq!(warnings, vec![
+            ("/docker_specs/system_packages/1", "git=1:2.39.2-1.1 pins an apt version, git is installed without the pin on the Alpine based 'rust:1.75-alpine3.19'"),
+            ("/docker_specs/system_packages/2", "Alpine doesn't need python3-venv, it isn't installed on the Alpine based 'rust:1.75-alpine3.19'"),
+        ]);
+
+        // apt based images get the packages as written
+        let spec = json!({"test_cmd": "cargo test", "log_parser_name": "cargo", "docker_specs": {"system_packages": ["libssl-dev", "git=1:2.39.2-1.1"]}});
+        let (dockerfile, _) = generate_dockerfile(&config(spec.clone()), "https://github.com/owner/repo", "abc123", "Rust", &RepoSource::Clone);
+        assert!(dockerfile.contains("    build-essential \\\n    libssl-dev \\\n    && rm -rf"), "{}", dockerfile);
+        assert!(dockerfile.contains("    git=1:2.39.2-1.1 \\\n"), "{}", dockerfile);
+        assert!(lint_base_image_compatibility(&config(spec), get_language("Rust")).is_empty());
+    }
+
+    #[test]
+    fn apk_versions() {
+        assert!(is_apk_version("2.43.0-r0"));
+        assert!(is_apk_version("1.2.3_rc1-r12"));
+        assert!(is_apk_version("8.5.0"));
+        assert!(!is_apk_version("1:2.39.2-1.1"));
+        assert!(!is_apk_version("2.34.1-1ubuntu1"));
+        assert!(!is_apk_version("1.0~rc1-r0"));
+        assert!(!is_apk_version("2.43.0-r"));
+        assert!(!is_apk_version(""));
+    }
+
+    fn javascript_dockerfile(docker_specs: Value) -> String {
+        let spec = json!({"test_cmd": "npx jest", "log_parser_name": "jest", "docker_specs": docker_specs});
+        generate_dockerfile(&config(spec), "https://github.com/owner/repo", "abc123", "Javascript", &RepoSource::Clone).0
+    }
+
+    #[test]
+    fn node_base_images_keep_their_node() {
+        let dockerfile = javascript_dockerfile(json!({"base_image": "node:18-bullseye"}));
+        assert!(dockerfile.starts_with("FROM node:18-bullseye\n"), "{}", dockerfile);
+        assert!(!dockerfile.contains("nodesource"), "{}", dockerfile);
+        assert!(!dockerfile.contains("install -y nodejs"), "{}", dockerfile);
+        assert!(!dockerfile.contains("ENV NODE_VERSION"), "{}", dockerfile);
+        assert!(dockerfile.contains("# Node comes with the base image\nRUN node -v && npm -v\n"), "{}", dockerfile);
+        // bullseye is Debian, where the package is chromium
+        assert!(dockerfile.contains("RUN apt-get update && apt-get install -y chromium\nENV CHROME_BIN=/usr/bin/chromium\nENV CHROME_PATH=/usr/bin/chromium\n"), "{}", dockerfile);
+
+        let dockerfile = javascript_dockerfile(json!({"node_version": "18"}));
+        assert!(dockerfile.starts_with("FROM ubuntu:22.04\n"), "{}", dockerfile);
+        assert!(dockerfile.contains("# Install node\nRUN bash -c \"set -eo pipefail && curl -fsSL https://deb.nodesource.com/setup_18.x | bash -\"\nRUN apt-get update && apt-get install -y nodejs\nRUN node -v && npm -v\n"), "{}", dockerfile);
+        assert!(dockerfile.contains("ENV NODE_VERSION 18\nRUN node -v\n"), "{}", dockerfile);
+        assert!(dockerfile.contains("apt-get install -y chromium-browser\nENV CHROME_BIN=/usr/bin/chromium-browser\n"), "{}", dockerfile);
+
+        // Other Ubuntu based images still need Node installed
+        let dockerfile = javascript_dockerfile(json!({"base_image": "nvidia/cuda:12.2.0-devel-ubuntu22.04"}));
+        assert!(dockerfile.contains("setup_20.x"), "{}", dockerfile);
+        assert!(dockerfile.contains("apt-get install -y chromium-browser"), "{}", dockerfile);
+    }
+
+    #[test]
+    fn base_image_names() {
+        assert!(image_provides_node("node:18-bullseye"));
+        assert!(image_provides_node("node"));
+        assert!(image_provides_node("docker.io/library/node:20-slim@sha256:abc"));
+        assert!(!image_provides_node("nodered/node-red:3"));
+        assert!(!image_provides_node("ubuntu:22.04"));
+        assert!(is_ubuntu_image("nvidia/cuda:12.2.0-devel-ubuntu22.04"));
+        assert!(is_ubuntu_image("buildpack-deps:jammy"));
+        assert!(!is_ubuntu_image("node:18-bullseye"));
+        assert!(!is_ubuntu_image("debian:bookworm-slim"));
+    }
+
+    #[test]
+    fn cpp_sources_list_edit_is_guarded() {
+        let spec = json!({"test_cmd": "ctest", "log_parser_name": "ctest", "docker_specs": {"base_image": "debian:bookworm"}});
+        let (dockerfile, _) = generate_dockerfile(&config(spec), "https://github.com/owner/repo", "abc123", "C/CPP", &RepoSource::Clone);
+        assert!(dockerfile.starts_with("FROM debian:bookworm\n"), "{}", dockerfile);
+        assert!(dockerfile.contains("RUN if [ -f /etc/apt/sources.list ]; then sed -i 's/^# deb-src/deb-src/' /etc/apt/sources.list; fi\n"), "{}", dockerfile);
+    }
+
+    #[test]
+    fn javascript_base_image_lint() {
+        let spec = |docker_specs: Value| json!({"test_cmd": "npx jest", "log_parser_name": "jest", "docker_specs": docker_specs});
+        assert_eq!(
+            lint(lint_base_image_compatibility, "Javascript", spec(json!({"base_image": "node:18-bullseye", "node_version": "20"}))),
+            vec!["node_version 20 is ignored, 'node:18-bullseye' brings its own Node"]
+        );
+        assert_eq!(
+            lint(lint_base_image_compatibility, "Javascript", spec(json!({"base_image": "node:20-bookworm"}))),
+            vec!["The Javascript Dockerfile installs python2, which 'node:20-bookworm' doesn't package"]
+        );
+        assert!(lint(lint_base_image_compatibility, "Javascript", spec(json!({"base_image": "node:18-bullseye"}))).is_empty());
+        assert!(lint(lint_base_image_compatibility, "Javascript", spec(json!({"node_version": "20"}))).is_empty());
+    }
+
+    #[test]
+    fn only_the_first_from_is_replaced() {
+        let dockerfile = "# syntax=docker/dockerfile:1\nFROM golang:1.22 AS build\nRUN go build ./...\n\n  from debian:bookworm\nCOPY --from=build /out /out\nFROM scratch";
+        assert_eq!(
+            replace_base_image(dockerfile, "golang:1.22-alpine"),
+            "# syntax=docker/dockerfile:1\nFROM golang:1.22-alpine AS build\nRUN go build ./...\n\n  from debian:bookworm\nCOPY --from=build /out /out\nFROM scratch"
+        );
+        let dockerfile = "RUN echo\n  from --platform=linux/amd64 ubuntu:22.04\nFROM ubuntu:24.04";
+        assert_eq!(replace_base_image(dockerfile, "debian:12"), "RUN echo\n  from --platform=linux/amd64 debian:12\nFROM ubuntu:24.04");
+        assert_eq!(replace_base_image("RUN echo FROM", "debian:12"), "RUN echo FROM");
+    }
+
+    #[test]
+    fn checkout_steps_per_source() {
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(
+            checkout_steps(&RepoSource::Clone, "https://github.com/owner/repo", commit),
+            "RUN git clone --depth 1 -o origin https://github.com/owner/repo /testbed
+RUN chmod -R 777 /testbed
+RUN git fetch origin 0123456789abcdef0123456789abcdef01234567
+RUN git reset --hard 0123456789abcdef0123456789abcdef01234567
+RUN git remote remove origin"
+        );
+        let exported = "COPY repo/ /testbed/
+RUN chmod -R 777 /testbed
+RUN git init -q && git add -A -f && git -c user.name=swebench -c user.email=swebench@localhost commit -q --no-verify -m \"0123456789abcdef0123456789abcdef01234567\"";
+        assert_eq!(checkout_steps(&RepoSource::Local { path: "/src/repo".to_string() }, "https://github.com/owner/repo", commit), exported);
+        assert_eq!(checkout_steps(&RepoSource::Mirror, "https://github.com/owner/repo", commit), exported);
+
+        let spec = json!({"test_cmd": "go test ./...", "log_parser_name": "gotest"});
+        let (dockerfile, _) = generate_dockerfile(&config(spec), "https://github.com/owner/repo", commit, "Go", &RepoSource::Mirror);
+        assert!(dockerfile.contains(&format!("WORKDIR /testbed/\n{}\n", exported)), "{}", dockerfile);
+        assert!(!dockerfile.contains("git clone"), "{}", dockerfile);
+    }
+
+    #[test]
+    fn build_context_size() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Dockerfile"), "FROM scratch\n").unwrap();
+        fs::create_dir_all(dir.path().join("repo/src")).unwrap();
+        fs::write(dir.path().join("repo/README.md"), vec![b'a'; 1000]).unwrap();
+        fs::write(dir.path().join("repo/src/lib.rs"), vec![b'b'; 2000]).unwrap();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        assert_eq!(directory_size(dir.path()).unwrap(), (3, 13 + 1000 + 2000));
+
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KB");
+        assert_eq!(format_size(3013), "2.9 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 + 512 * 1024), "5.5 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024 * 1024), "3072.0 GB");
+    }
+
+    // The messages `rule` warns about for `spec`
+    fn lint(rule: LintRule, language: &str, spec: Value) -> Vec<String> {
+        rule(&config(spec), get_language(language)).into_iter().map(|warning| warning.message).collect()
+    }
+
+    #[test]
+    fn log_parser_mismatch_lint() {
+        let spec = |test_cmd: &str, parser: &str| json!({"test_cmd": test_cmd, "log_parser_name": parser});
+        assert_eq!(
+            lint(lint_log_parser_mismatch, "Javascript", spec("npx jest --ci", "mocha")),
+            vec!["test_cmd runs jest but log_parser_name is 'mocha'"]
+        );
+        assert_eq!(
+            lint(lint_log_parser_mismatch, "Python", spec("cd tests && ./venv/bin/pytest -rA", "django")),
+            vec!["test_cmd runs pytest but log_parser_name is 'django'"]
+        );
+        assert!(lint(lint_log_parser_mismatch, "Javascript", spec("npx jest --ci", "jest")).is_empty());
+        assert!(lint(lint_log_parser_mismatch, "Javascript", spec("npx jest --ci", "agentic")).is_empty());
+        assert!(lint(lint_log_parser_mismatch, "Javascript", spec("npx jest --ci", "regex")).is_empty());
+        // Rust has no jest parser to suggest
+        assert!(lint(lint_log_parser_mismatch, "Rust", spec("npx jest", "cargo")).is_empty());
+        // A runner name inside another word isn't the runner
+        assert!(lint(lint_log_parser_mismatch, "Javascript", spec("npm run jest-tests", "mocha")).is_empty());
+    }
+
+    #[test]
+    fn duplicate_install_command_lint() {
+        let warnings = lint(lint_duplicate_install_command, "Javascript", json!({
+            "test_cmd": "npm test",
+            "log_parser_name": "jest",
+            "pre_install": ["apt-get update", "npm ci"],
+            "install": ["npm run build", " npm ci "],
+        }));
+        assert_eq!(warnings, vec!["' npm ci ' already runs in pre_install"]);
+        let config = config(json!({"test_cmd": "npm test", "log_parser_name": "jest", "pre_install": ["npm ci"], "install": ["npm ci"]}));
+        assert_eq!(lint_duplicate_install_command(&config, get_language("Javascript"))[0].pointer, "/install/0");
+
+        assert!(lint(lint_duplicate_install_command, "Javascript", json!({
+            "test_cmd": "npm test",
+            "log_parser_name": "jest",
+            "pre_install": ["npm ci"],
+            "install": ["npm ci --ignore-scripts"],
+        })).is_empty());
+        assert!(lint(lint_duplicate_install_command, "Javascript", json!({"test_cmd": "npm test", "log_parser_name": "jest", "install": ["npm ci"]})).is_empty());
+    }
+
+    #[test]
+    fn docker_spec_version_lint() {
+        let spec = |docker_specs: Value| json!({"test_cmd": "npm test", "log_parser_name": "jest", "docker_specs": docker_specs});
+        assert_eq!(
+            lint(lint_docker_spec_version, "Javascript", spec(json!({"node_version": "v20", "pnpm_version": "9.5.0"}))),
+            vec!["node_version 'v20' doesn't look like a version number"]
+        );
+        assert_eq!(
+            lint(lint_docker_spec_version, "Javascript", spec(json!({"node_version": "hydrogen"}))),
+            vec!["node_version 'hydrogen' doesn't look like a version number"]
+        );
+        assert!(lint(lint_docker_spec_version, "Javascript", spec(json!({"node_version": "20", "ubuntu_version": "22.04"}))).is_empty());
+        assert!(lint(lint_docker_spec_version, "Javascript", spec(json!({"node_version": "LTS"}))).is_empty());
+        assert!(lint(lint_docker_spec_version, "Rust", spec(json!({"rust_version": "nightly"}))).is_empty());
+        assert!(lint(lint_docker_spec_version, "Javascript", json!({"test_cmd": "npm test", "log_parser_name": "jest"})).is_empty());
+    }
+
+    #[test]
+    fn system_package_provided_lint() {
+        let spec = |packages: Value| json!({"test_cmd": "cargo test", "log_parser_name": "cargo", "docker_specs": {"system_packages": packages}});
+        assert_eq!(
+            lint(lint_system_package_provided, "Rust", spec(json!(["libpq-dev", "git"]))),
+            vec!["The Rust image already installs git"]
+        );
+        let config = config(spec(json!(["libpq-dev", "git"])));
+        assert_eq!(lint_system_package_provided(&config, get_language("Rust"))[0].pointer, "/docker_specs/system_packages/1");
+
+        assert!(lint(lint_system_package_provided, "Rust", spec(json!(["libpq-dev", "protobuf-compiler"]))).is_empty());
+        // A pinned version is a deliberate choice, not a duplicate
+        assert!(lint(lint_system_package_provided, "Rust", spec(json!(["git=1:2.39.2-1.1"]))).is_empty());
+    }
+
+    #[test]
+    fn base_image_compatibility_lint() {
+        let spec = |image: &str| json!({"test_cmd": "cargo test", "log_parser_name": "cargo", "docker_specs": {"base_image": image}});
+        assert_eq!(
+            lint(lint_base_image_compatibility, "Javascript", spec("alpine:3.19")),
+            vec!["The Javascript Dockerfile installs its toolchain with apt, which the Alpine based 'alpine:3.19' doesn't have"]
+        );
+        assert_eq!(
+            lint(lint_base_image_compatibility, "Rust", spec("fedora:39")),
+            vec!["'fedora:39' doesn't look Debian, Ubuntu or Alpine based, the generated Dockerfile installs packages with apt"]
+        );
+        assert!(lint(lint_base_image_compatibility, "Rust", spec("rust:1.75-alpine3.19")).is_empty());
+        assert!(lint(lint_base_image_compatibility, "Rust", spec("nvidia/cuda:12.2.0-devel-ubuntu22.04")).is_empty());
+        assert!(lint(lint_base_image_compatibility, "Rust", json!({"test_cmd": "cargo test", "log_parser_name": "cargo"})).is_empty());
+    }
+
+    #[test]
+    fn disabled_lint_rules_are_skipped() {
+        let input = r#"{
+  "test_cmd": "npx jest {test_files}",
+  "log_parser_name": "jest",
+  "docker_specs": { "node_version": "v20", "system_packages": ["git"] }
+}"#;
+        let config = config(serde_json::from_str(input).unwrap());
+        let rules = |disabled: &str| {
+            run_lint_rules(input, SpecFormat::Json, &config, "Javascript", disabled)
+                .into_iter()
+                .map(|issue| issue.rule.unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rules(""), ["test-cmd-placeholder", "docker-spec-version", "system-package-provided"]);
+        // Written the way the README shows it, with spaces after the commas
+        assert_eq!(rules("docker-spec-version, system-package-provided"), ["test-cmd-placeholder"]);
+        assert_eq!(rules(" test-cmd-placeholder ,docker-spec-version "), ["system-package-provided"]);
+        // Unknown ids don't disable anything
+        assert_eq!(rules("docker-spec"), ["test-cmd-placeholder", "docker-spec-version", "system-package-provided"]);
+
+        let issues = run_lint_rules(input, SpecFormat::Json, &config, "Javascript", "");
+        assert_eq!((issues[1].pointer.as_str(), issues[1].line, issues[1].column), ("/docker_specs/node_version", 4, 37));
+        assert_eq!(issues[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn placeholder_lint_leaves_shell_variables_alone() {
+        let language = get_language("Python");
+        let lint = |test_cmd: &str| {
+            let config = config(json!({"test_cmd": test_cmd, "log_parser_name": "pytest"}));
+            lint_test_cmd_placeholder(&config, language).into_iter().map(|warning| warning.message).collect::<Vec<_>>()
+        };
+        assert!(lint("pytest ${PYTEST_ARGS} -x").is_empty());
+        assert!(lint("pytest ${PYTEST_ARGS:-} $HOME").is_empty());
+        assert_eq!(
+            lint("pytest ${PYTEST_ARGS} {test_files}"),
+            vec!["test_cmd contains the placeholder {test_files}, placeholders are not substituted"]
+        );
+    }
+}
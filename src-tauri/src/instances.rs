@@ -0,0 +1,367 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::languages::{DEFAULT_LANGUAGE, REGISTRY};
+
+// A SWE-bench style task instance, with what a tab needs to build and test it
+#[derive(Serialize, Clone, Debug)]
+pub struct InstanceRecord {
+    pub instance_id: Option<String>,
+    // `owner/name`, as in the dataset
+    pub repo: String,
+    pub github_repo_url: String,
+    pub base_commit: String,
+    pub patch: String,
+    pub test_patch: String,
+    #[serde(rename = "FAIL_TO_PASS")]
+    pub fail_to_pass: Vec<String>,
+    #[serde(rename = "PASS_TO_PASS")]
+    pub pass_to_pass: Vec<String>,
+    // Files the test_patch touches, a starting point for the tab's test files
+    pub test_files: Vec<String>,
+    pub language: String,
+    // Why `language` was picked, shown next to it so a wrong guess is easy to spot
+    pub language_reason: String,
+}
+
+// Parse an instance from a JSON object, or from a JSONL file. For JSONL `line` picks
+// the 1-based line to use, the first non-empty one by default.
+pub fn parse_instance(input: &str, line: Option<usize>) -> Result<InstanceRecord, String> {
+    let value = match serde_json::from_str::<Value>(input) {
+        Ok(value) if line.is_none() => value,
+        _ => {
+            let lines: Vec<(usize, &str)> = input
+                .lines()
+                .enumerate()
+                .map(|(index, text)| (index + 1, text))
+                .filter(|(_, text)| !text.trim().is_empty())
+                .collect();
+            let (number, text) = match line {
+                Some(line) => *lines
+                    .iter()
+                    .find(|(number, _)| *number == line)
+                    .ok_or_else(|| format!("Line {} is empty or past the end of the input", line))?,
+                None => *lines.first().ok_or("The input is empty")?,
+            };
+            serde_json::from_str(text).map_err(|e| format!("Invalid instance JSON on line {}: {}", number, e))?
+        }
+    };
+    let instance = value.as_object().ok_or("The instance must be a JSON object")?;
+
+    let repo = required_string(instance, "repo")?;
+    let github_repo_url = normalize_repo_url(&repo)?;
+    let patch = optional_string(instance, "patch")?;
+    let test_patch = optional_string(instance, "test_patch")?;
+    let (language, language_reason) = detect_language(instance, &repo, &patch, &test_patch);
+
+    Ok(InstanceRecord {
+        instance_id: instance.get("instance_id").and_then(Value::as_str).map(str::to_string),
+        repo: github_repo_url.trim_start_matches("https://github.com/").to_string(),
+        github_repo_url,
+        base_commit: required_string(instance, "base_commit")?,
+        fail_to_pass: test_list(instance, "FAIL_TO_PASS")?,
+        pass_to_pass: test_list(instance, "PASS_TO_PASS")?,
+        test_files: diff_files(&test_patch),
+        patch,
+        test_patch,
+        language,
+        language_reason,
+    })
+}
+
+fn required_string(instance: &Map<String, Value>, key: &str) -> Result<String, String> {
+    match instance.get(key) {
+        Some(Value::String(value)) if !value.trim().is_empty() => Ok(value.trim().to_string()),
+        Some(Value::String(_)) | None => Err(format!("The instance has no {}", key)),
+        Some(other) => Err(format!("{} must be a string, got {}", key, other)),
+    }
+}
+
+fn optional_string(instance: &Map<String, Value>, key: &str) -> Result<String, String> {
+    match instance.get(key) {
+        None | Some(Value::Null) => Ok(String::new()),
+        Some(Value::String(value)) => Ok(value.clone()),
+        Some(other) => Err(format!("{} must be a string, got {}", key, other)),
+    }
+}
+
+// The published datasets store the test lists as JSON encoded strings, hand written
+// instances tend to use plain arrays
+fn test_list(instance: &Map<String, Value>, key: &str) -> Result<Vec<String>, String> {
+    let value = match instance.get(key) {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::String(encoded)) if encoded.trim().is_empty() => return Ok(Vec::new()),
+        Some(Value::String(encoded)) => {
+            serde_json::from_str(encoded).map_err(|e| format!("{} is not a JSON list: {}", key, e))?
+        }
+        Some(value) => value.clone(),
+    };
+    match value {
+        Value::Array(tests) => tests
+            .into_iter()
+            .map(|test| match test {
+                Value::String(test) => Ok(test),
+                other => Err(format!("{} must only contain test names, got {}", key, other)),
+            })
+            .collect(),
+        other => Err(format!("{} must be a list of test names, got {}", key, other)),
+    }
+}
+
+// `owner/name`, a GitHub web or SSH URL, with or without .git, to https://github.com/owner/name.
+// URLs must be on github.com itself, web URLs may go on past the repository (`/tree/main`).
+pub fn normalize_repo_url(repo: &str) -> Result<String, String> {
+    let trimmed = repo.trim().trim_end_matches('/');
+    let (path, web_url) = if let Some(path) = trimmed.strip_prefix("git@github.com:") {
+        (path, false)
+    } else if let Some((scheme, rest)) = trimmed.split_once("://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority.rsplit('@').next().unwrap_or(authority).to_lowercase();
+        let known_scheme = ["https", "http", "ssh", "git"].contains(&scheme.to_lowercase().as_str());
+        if !known_scheme || (host != "github.com" && host != "www.github.com") {
+            return Err(format!("{} is not a GitHub repository", repo));
+        }
+        (path, true)
+    } else if trimmed.contains(':') {
+        // host:path, an SSH remote somewhere other than GitHub
+        return Err(format!("{} is not a GitHub repository", repo));
+    } else {
+        (trimmed, false)
+    };
+
+    let mut parts = path.splitn(3, '/');
+    let owner = parts.next().unwrap_or_default();
+    let name = parts.next().unwrap_or_default();
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    let valid_owner = !owner.is_empty() && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    let valid_name = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_');
+    if !valid_owner || !valid_name || (parts.next().is_some() && !web_url) {
+        return Err(format!("Expected repo to be owner/name or a GitHub URL, got {}", repo));
+    }
+    Ok(format!("https://github.com/{}/{}", owner, name))
+}
+
+fn language_for_extension(extension: &str) -> Option<&'static str> {
+    match extension {
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "vue" | "svelte" => Some("Javascript"),
+        "rs" => Some("Rust"),
+        "go" => Some("Go"),
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => Some("C/CPP"),
+        "java" | "kt" | "groovy" | "gradle" => Some("Java"),
+        "py" | "pyi" => Some("Python"),
+        _ => None,
+    }
+}
+
+// In order: a `language` field on the instance, the extensions of the files the patches
+// touch, hints in the repository name, and finally the default language
+fn detect_language(instance: &Map<String, Value>, repo: &str, patch: &str, test_patch: &str) -> (String, String) {
+    if let Some(requested) = instance.get("language").and_then(Value::as_str) {
+        if let Some(language) = REGISTRY.languages.iter().find(|l| l.name.eq_ignore_ascii_case(requested.trim())) {
+            return (language.name.clone(), "the instance's language field".to_string());
+        }
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for file in diff_files(patch).iter().chain(diff_files(test_patch).iter()) {
+        let extension = file.rsplit_once('.').map(|(_, extension)| extension.to_lowercase());
+        if let Some(language) = extension.as_deref().and_then(language_for_extension) {
+            *counts.entry(language).or_default() += 1;
+        }
+    }
+    // Ties go to the alphabetically first language so the guess is stable
+    if let Some((language, count)) = counts.into_iter().max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0))) {
+        return (language.to_string(), format!("the patches change {} {} file(s)", count, language));
+    }
+
+    // Whole words of the name, so jsonschema-tools isn't Javascript or happy-app Python
+    let name = repo.rsplit('/').next().unwrap_or(repo).to_lowercase();
+    let words: Vec<&str> = name.split(['-', '_', '.']).filter(|word| !word.is_empty()).collect();
+    let hints: &[(&str, &str)] = &[
+        ("javascript", "Javascript"),
+        ("js", "Javascript"),
+        ("node", "Javascript"),
+        ("rust", "Rust"),
+        ("rs", "Rust"),
+        ("golang", "Go"),
+        ("go", "Go"),
+        ("cpp", "C/CPP"),
+        ("java", "Java"),
+        ("python", "Python"),
+        ("py", "Python"),
+    ];
+    for (hint, language) in hints {
+        if words.contains(hint) {
+            return (language.to_string(), format!("the repository name has the word \"{}\"", hint));
+        }
+    }
+    // Package naming habits, like pyyaml and vuejs
+    for word in &words {
+        if word.starts_with("py") {
+            return ("Python".to_string(), format!("the repository name has \"{}\", which starts with \"py\"", word));
+        }
+        if word.ends_with("js") {
+            return ("Javascript".to_string(), format!("the repository name has \"{}\", which ends with \"js\"", word));
+        }
+    }
+
+    (DEFAULT_LANGUAGE.to_string(), "no heuristic matched, using the default language".to_string())
+}
+
+// Paths of the files a unified diff touches, in order and without duplicates
+pub fn diff_files(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in diff.lines() {
+        let Some(paths) = line.strip_prefix("diff --git a/") else {
+            continue;
+        };
+        // `a/<path> b/<path>`, the new path is the one that exists after the patch
+        let file = paths.rsplit_once(" b/").map(|(_, new)| new).unwrap_or(paths);
+        if !files.iter().any(|f| f == file) {
+            files.push(file.to_string());
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn normalizes_github_repositories() {
+        for repo in [
+            "owner/repo",
+            " owner/repo.git ",
+            "https://github.com/owner/repo",
+            "https://github.com/owner/repo.git",
+            "https://github.com/owner/repo/",
+            "http://www.github.com/owner/repo",
+            "HTTPS://GitHub.com/owner/repo",
+            "https://github.com/owner/repo/tree/main/src",
+            "https://github.com/owner/repo/pull/42",
+            "git@github.com:owner/repo.git",
+            "ssh://git@github.com/owner/repo.git",
+        ] {
+            assert_eq!(normalize_repo_url(repo), Ok("https://github.com/owner/repo".to_string()), "{}", repo);
+        }
+        assert_eq!(normalize_repo_url("django/django"), Ok("https://github.com/django/django".to_string()));
+        assert_eq!(normalize_repo_url("owner/my_repo.js"), Ok("https://github.com/owner/my_repo.js".to_string()));
+    }
+
+    #[test]
+    fn rejects_other_hosts() {
+        for repo in [
+            "https://notgithub.com/a/b",
+            "https://evil.example/github.com/a/b",
+            "https://github.com.evil.example/a/b",
+            "https://evil.example/?github.com/a/b",
+            "https://github.com@evil.example/a/b",
+            "ftp://github.com/a/b",
+            "git@gitlab.com:a/b",
+            "a:b/c",
+        ] {
+            assert_eq!(normalize_repo_url(repo), Err(format!("{} is not a GitHub repository", repo)), "{}", repo);
+        }
+        for repo in ["", "owner", "owner/", "/repo", "a/b/c", "owner/..", "owner/repo name", "https://github.com/owner"] {
+            assert_eq!(
+                normalize_repo_url(repo),
+                Err(format!("Expected repo to be owner/name or a GitHub URL, got {}", repo)),
+                "{}",
+                repo
+            );
+        }
+    }
+
+    fn language(repo: &str) -> (String, String) {
+        detect_language(&Map::new(), repo, "", "")
+    }
+
+    #[test]
+    fn repository_name_hints_match_whole_words() {
+        assert_eq!(language("owner/jsonschema-tools"), ("Javascript".to_string(), "no heuristic matched, using the default language".to_string()));
+        assert_eq!(language("owner/happy-app").1, "no heuristic matched, using the default language");
+        assert_eq!(language("owner/gopher-stats").1, "no heuristic matched, using the default language");
+        assert_eq!(language("owner/trust-store").1, "no heuristic matched, using the default language");
+
+        assert_eq!(language("chartjs/Chart.js"), ("Javascript".to_string(), "the repository name has the word \"js\"".to_string()));
+        assert_eq!(language("owner/ripgrep-rs"), ("Rust".to_string(), "the repository name has the word \"rs\"".to_string()));
+        assert_eq!(language("google/go-github").0, "Go");
+        assert_eq!(language("owner/json_cpp").0, "C/CPP");
+        assert_eq!(language("owner/awesome-java").0, "Java");
+        assert_eq!(language("owner/py-spy").0, "Python");
+        assert_eq!(language("yaml/pyyaml"), ("Python".to_string(), "the repository name has \"pyyaml\", which starts with \"py\"".to_string()));
+        assert_eq!(language("vuejs/vuejs-core"), ("Javascript".to_string(), "the repository name has \"vuejs\", which ends with \"js\"".to_string()));
+    }
+
+    #[test]
+    fn language_field_and_patches_beat_the_name() {
+        let instance = json!({"language": "rust"});
+        assert_eq!(
+            detect_language(instance.as_object().unwrap(), "owner/pyyaml", "", ""),
+            ("Rust".to_string(), "the instance's language field".to_string())
+        );
+        let patch = "diff --git a/src/main.go b/src/main.go\ndiff --git a/README.md b/README.md\n";
+        let test_patch = "diff --git a/src/main_test.go b/src/main_test.go\n";
+        assert_eq!(
+            detect_language(&Map::new(), "owner/pyyaml", patch, test_patch),
+            ("Go".to_string(), "the patches change 2 Go file(s)".to_string())
+        );
+    }
+
+    const INSTANCE: &str = r#"{"instance_id": "owner__repo-1", "repo": "owner/repo", "base_commit": "abc123", "patch": "diff --git a/lib/a.py b/lib/a.py\n", "test_patch": "diff --git a/tests/test_a.py b/tests/test_a.py\n", "FAIL_TO_PASS": "[\"tests/test_a.py::test_new\"]", "PASS_TO_PASS": "[\"tests/test_a.py::test_old\", \"tests/test_a.py::test_other[1-2]\"]"}"#;
+
+    #[test]
+    fn parses_an_instance_with_json_encoded_test_lists() {
+        let instance = parse_instance(INSTANCE, None).unwrap();
+        assert_eq!(instance.instance_id.as_deref(), Some("owner__repo-1"));
+        assert_eq!(instance.repo, "owner/repo");
+        assert_eq!(instance.github_repo_url, "https://github.com/owner/repo");
+        assert_eq!(instance.base_commit, "abc123");
+        assert_eq!(instance.fail_to_pass, vec!["tests/test_a.py::test_new"]);
+        assert_eq!(instance.pass_to_pass, vec!["tests/test_a.py::test_old", "tests/test_a.py::test_other[1-2]"]);
+        assert_eq!(instance.test_files, vec!["tests/test_a.py"]);
+        assert_eq!(instance.language, "Python");
+
+        // Plain arrays work as well
+        let instance = parse_instance(r#"{"repo": "owner/repo", "base_commit": "abc123", "FAIL_TO_PASS": ["a"], "PASS_TO_PASS": ""}"#, None).unwrap();
+        assert_eq!(instance.fail_to_pass, vec!["a"]);
+        assert!(instance.pass_to_pass.is_empty());
+
+        let error = parse_instance(r#"{"repo": "owner/repo", "base_commit": "abc123", "FAIL_TO_PASS": "test_a"}"#, None).unwrap_err();
+        assert!(error.starts_with("FAIL_TO_PASS is not a JSON list: "), "{}", error);
+        let error = parse_instance(r#"{"repo": "owner/repo", "base_commit": "abc123", "PASS_TO_PASS": "[1]"}"#, None).unwrap_err();
+        assert_eq!(error, "PASS_TO_PASS must only contain test names, got 1");
+        let error = parse_instance(r#"{"repo": "https://gitlab.com/owner/repo", "base_commit": "abc123"}"#, None).unwrap_err();
+        assert_eq!(error, "https://gitlab.com/owner/repo is not a GitHub repository");
+        assert_eq!(parse_instance(r#"{"repo": "owner/repo"}"#, None).unwrap_err(), "The instance has no base_commit");
+    }
+
+    #[test]
+    fn picks_a_line_of_jsonl() {
+        let second = INSTANCE.replace("owner__repo-1", "owner__repo-2").replace("abc123", "def456");
+        let jsonl = format!("{}\n\n{}\n", INSTANCE, second);
+
+        // The first non-empty line by default
+        assert_eq!(parse_instance(&jsonl, None).unwrap().instance_id.as_deref(), Some("owner__repo-1"));
+        assert_eq!(parse_instance(&jsonl, Some(1)).unwrap().instance_id.as_deref(), Some("owner__repo-1"));
+        let instance = parse_instance(&jsonl, Some(3)).unwrap();
+        assert_eq!(instance.instance_id.as_deref(), Some("owner__repo-2"));
+        assert_eq!(instance.base_commit, "def456");
+
+        assert_eq!(parse_instance(&jsonl, Some(2)).unwrap_err(), "Line 2 is empty or past the end of the input");
+        assert_eq!(parse_instance(&jsonl, Some(9)).unwrap_err(), "Line 9 is empty or past the end of the input");
+        // A single object also takes a line number
+        assert_eq!(parse_instance(INSTANCE, Some(1)).unwrap().instance_id.as_deref(), Some("owner__repo-1"));
+
+        let error = parse_instance(&format!("{}\n{{\"repo\": ", INSTANCE), Some(2)).unwrap_err();
+        assert!(error.starts_with("Invalid instance JSON on line 2: "), "{}", error);
+        assert_eq!(parse_instance("  \n", None).unwrap_err(), "The input is empty");
+        assert_eq!(parse_instance("[1]", None).unwrap_err(), "The instance must be a JSON object");
+    }
+}
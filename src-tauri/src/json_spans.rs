@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+// 1-based line and column, columns count UTF-16 code units like the Monaco editor
+// does, so an emoji takes two
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+// Where a value sits in the source text, `end` is exclusive. Object members also
+// remember the span of their key, which is what gets underlined for unknown keys.
+#[derive(Clone, Copy, Debug)]
+pub struct ValueSpan {
+    pub start: Position,
+    pub end: Position,
+    pub key: Option<(Position, Position)>,
+}
+
+// Maps every JSON pointer in the document ("" for the root, "/docker_specs/node_version",
+// "/install/0", ...) to its span. Expects input that serde_json already accepted, so
+// it doesn't try to report syntax errors itself and stops at the first surprise.
+pub fn parse_spans(input: &str) -> HashMap<String, ValueSpan> {
+    let mut scanner = Scanner {
+        chars: input.chars().collect(),
+        index: 0,
+        position: Position { line: 1, column: 1 },
+        spans: HashMap::new(),
+    };
+    scanner.skip_whitespace();
+    scanner.value(String::new(), None);
+    scanner.spans
+}
+
+// Escape a key for use in a JSON pointer (RFC 6901)
+pub fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    index: usize,
+    position: Position,
+    spans: HashMap<String, ValueSpan>,
+}
+
+impl Scanner {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.index += 1;
+        if ch == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += ch.len_utf16();
+        }
+        Some(ch)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.bump();
+        }
+    }
+
+    fn value(&mut self, pointer: String, key: Option<(Position, Position)>) -> Option<()> {
+        let start = self.position;
+        match self.peek()? {
+            '{' => self.object(&pointer)?,
+            '[' => self.array(&pointer)?,
+            '"' => {
+                self.string()?;
+            }
+            _ => {
+                // Numbers, true, false and null all end at a delimiter
+                while let Some(ch) = self.peek() {
+                    if matches!(ch, ',' | '}' | ']' | ' ' | '\t' | '\n' | '\r') {
+                        break;
+                    }
+                    self.bump();
+                }
+            }
+        }
+        self.spans.insert(pointer, ValueSpan { start, end: self.position, key });
+        Some(())
+    }
+
+    fn object(&mut self, pointer: &str) -> Option<()> {
+        self.bump();
+        self.skip_whitespace();
+        if self.peek()? == '}' {
+            self.bump();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key_start = self.position;
+            let key = self.string()?;
+            let key_end = self.position;
+            self.skip_whitespace();
+            if self.bump()? != ':' {
+                return None;
+            }
+            self.skip_whitespace();
+            let member = format!("{}/{}", pointer, escape_pointer_token(&key));
+            self.value(member, Some((key_start, key_end)))?;
+            self.skip_whitespace();
+            match self.bump()? {
+                ',' => continue,
+                '}' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self, pointer: &str) -> Option<()> {
+        self.bump();
+        self.skip_whitespace();
+        if self.peek()? == ']' {
+            self.bump();
+            return Some(());
+        }
+        let mut index = 0;
+        loop {
+            self.skip_whitespace();
+            self.value(format!("{}/{}", pointer, index), None)?;
+            index += 1;
+            self.skip_whitespace();
+            match self.bump()? {
+                ',' => continue,
+                ']' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    // Consumes a string literal and returns its decoded contents
+    fn string(&mut self) -> Option<String> {
+        if self.bump()? != '"' {
+            return None;
+        }
+        let mut decoded = String::new();
+        loop {
+            match self.bump()? {
+                '"' => return Some(decoded),
+                '\\' => match self.bump()? {
+                    'n' => decoded.push('\n'),
+                    't' => decoded.push('\t'),
+                    'r' => decoded.push('\r'),
+                    'b' => decoded.push('\u{8}'),
+                    'f' => decoded.push('\u{c}'),
+                    'u' => {
+                        let high = self.hex4()?;
+                        let code = if (0xD800..0xDC00).contains(&high) && self.peek() == Some('\\') {
+                            self.bump();
+                            self.bump();
+                            let low = self.hex4()?;
+                            0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                        } else {
+                            high
+                        };
+                        decoded.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    other => decoded.push(other),
+                },
+                ch => decoded.push(ch),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..4 {
+            value = value * 16 + self.bump()?.to_digit(16)?;
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    fn span(spans: &HashMap<String, ValueSpan>, pointer: &str) -> (Position, Position) {
+        let span = spans.get(pointer).unwrap_or_else(|| panic!("no span for {:?}", pointer));
+        (span.start, span.end)
+    }
+
+    #[test]
+    fn maps_members_and_keys() {
+        let spans = parse_spans("{\n  \"test_cmd\": \"npm test\",\n  \"docker_specs\": {\"node_version\": \"18\"}\n}");
+        assert_eq!(span(&spans, ""), (position(1, 1), position(4, 2)));
+        assert_eq!(span(&spans, "/test_cmd"), (position(2, 15), position(2, 25)));
+        assert_eq!(spans["/test_cmd"].key, Some((position(2, 3), position(2, 13))));
+        assert_eq!(span(&spans, "/docker_specs/node_version"), (position(3, 36), position(3, 40)));
+    }
+
+    #[test]
+    fn escapes_keys_into_pointer_tokens() {
+        let spans = parse_spans(r#"{"a/b": 1, "c~d": 2, "e\"f": 3, "g\u0068": 4}"#);
+        assert_eq!(span(&spans, "/a~1b"), (position(1, 9), position(1, 10)));
+        assert_eq!(span(&spans, "/c~0d"), (position(1, 19), position(1, 20)));
+        assert_eq!(span(&spans, "/e\"f"), (position(1, 30), position(1, 31)));
+        assert_eq!(span(&spans, "/gh"), (position(1, 44), position(1, 45)));
+    }
+
+    #[test]
+    fn decodes_surrogate_pairs() {
+        let spans = parse_spans(r#"{"\ud83d\ude00": "x"}"#);
+        assert_eq!(span(&spans, "/\u{1F600}"), (position(1, 18), position(1, 21)));
+    }
+
+    #[test]
+    fn counts_astral_characters_as_two_columns() {
+        // The emoji is one char but two UTF-16 code units, as Monaco counts it
+        let spans = parse_spans("{\"a\": \"\u{1F600}\", \"b\": true}");
+        assert_eq!(span(&spans, "/a"), (position(1, 7), position(1, 11)));
+        assert_eq!(span(&spans, "/b"), (position(1, 18), position(1, 22)));
+    }
+
+    #[test]
+    fn indexes_nested_arrays() {
+        let spans = parse_spans("{\"install\": [[\"a\", [1, 2]], []]}");
+        assert_eq!(span(&spans, "/install/0"), (position(1, 14), position(1, 27)));
+        assert_eq!(span(&spans, "/install/0/0"), (position(1, 15), position(1, 18)));
+        assert_eq!(span(&spans, "/install/0/1/1"), (position(1, 24), position(1, 25)));
+        assert_eq!(span(&spans, "/install/1"), (position(1, 29), position(1, 31)));
+    }
+}
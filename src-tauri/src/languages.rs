@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::commands::get_app_dir;
+
+// A language the spec editor knows about. Built-in languages are generated by the
+// generate_*_dockerfile functions in commands.rs, custom ones loaded from
+// ~/.swebench-debugger/languages/*.json must bring their own Dockerfile template.
+// The built-ins stay in code on purpose: they switch package managers with the base
+// image and Javascript writes a second script, neither of which a template can do.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LanguageDefinition {
+    pub name: String,
+    pub log_parsers: Vec<String>,
+    // JSON schema of the properties allowed under `docker_specs`
+    #[serde(default)]
+    pub docker_specs: HashMap<String, Value>,
+    // Values used for `docker_specs` keys the spec doesn't set
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    // Packages the image's package layer already installs, `docker_specs.system_packages`
+    // from the spec are added to these
+    #[serde(default)]
+    pub system_packages: Vec<String>,
+    // Dockerfile with {{placeholders}}, see render_dockerfile_template in commands.rs
+    #[serde(default)]
+    pub dockerfile_template: Option<String>,
+}
+
+pub const DEFAULT_LANGUAGE: &str = "Javascript";
+
+// Every language, and why the custom language files that weren't loaded were skipped
+#[derive(Serialize, Clone, Debug)]
+pub struct LanguageRegistry {
+    pub languages: Vec<LanguageDefinition>,
+    pub load_errors: Vec<String>,
+}
+
+lazy_static::lazy_static! {
+    pub static ref REGISTRY: LanguageRegistry = load_languages();
+}
+
+fn builtin(
+    name: &str,
+    log_parsers: &[&str],
+    docker_specs: Value,
+    defaults: &[(&str, &str)],
+    system_packages: &[&str],
+) -> LanguageDefinition {
+    LanguageDefinition {
+        name: name.to_string(),
+        log_parsers: log_parsers.iter().map(|s| s.to_string()).collect(),
+        docker_specs: serde_json::from_value(docker_specs).unwrap_or_default(),
+        defaults: defaults.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        system_packages: system_packages.iter().map(|s| s.to_string()).collect(),
+        dockerfile_template: None,
+    }
+}
+
+fn builtin_languages() -> Vec<LanguageDefinition> {
+    vec![
+        builtin(
+            "Javascript",
+            &["jest", "mocha", "vitest", "karma", "tap", "calypso", "chartjs", "marked", "p5js", "agentic"],
+            json!({
+                "ubuntu_version": {
+                    "type": "string",
+                    "description": "Ubuntu version for the Docker image"
+                },
+                "node_version": {
+                    "type": "string",
+                    "description": "Node.js version to install"
+                },
+                "pnpm_version": {
+                    "type": "string",
+                    "description": "PNPM version to install"
+                }
+            }),
+            &[("ubuntu_version", "22.04"), ("node_version", "20"), ("pnpm_version", "9.5.0")],
+            &[
+                "build-essential", "curl", "git", "libssl-dev", "software-properties-common", "wget", "gnupg", "jq",
+                "ca-certificates", "dbus", "ffmpeg", "imagemagick", "libcairo2-dev", "libpango1.0-dev", "libjpeg-dev",
+                "libgif-dev", "librsvg2-dev", "pkg-config",
+            ],
+        ),
+        builtin(
+            "Rust",
+            &["cargo", "agentic"],
+            json!({
+                "rust_version": {
+                    "type": "string",
+                    "description": "Rust version to use"
+                }
+            }),
+            &[("rust_version", "latest")],
+            &["wget", "git", "build-essential"],
+        ),
+        builtin(
+            "Go",
+            &["gotest", "gotest-json", "agentic"],
+            json!({
+                "go_version": {
+                    "type": "string",
+                    "description": "Go version to use"
+                }
+            }),
+            &[("go_version", "latest")],
+            &["wget", "git", "build-essential"],
+        ),
+        builtin(
+            "C/CPP",
+            &["doctest", "googletest", "ctest", "agentic"],
+            json!({
+                "ubuntu_version": {
+                    "type": "string",
+                    "description": "Ubuntu version for the Docker image"
+                }
+            }),
+            &[("ubuntu_version", "22.04")],
+            &[
+                "wget", "git", "build-essential", "libtool", "automake", "autoconf", "tcl", "bison", "flex", "cmake",
+                "python3", "python3-pip", "python3-venv", "python-is-python3",
+            ],
+        ),
+        builtin(
+            "Java",
+            &["surefire", "gradle", "agentic"],
+            json!({
+                "jdk_version": {
+                    "type": "string",
+                    "description": "Temurin JDK version to use"
+                },
+                "maven_version": {
+                    "type": "string",
+                    "description": "Maven version to install"
+                },
+                "gradle_version": {
+                    "type": "string",
+                    "description": "Gradle version to install, Gradle is not installed if omitted (projects using the Gradle wrapper don't need it)"
+                }
+            }),
+            &[("jdk_version", "17"), ("maven_version", "3.9.6")],
+            &["wget", "git", "unzip", "build-essential"],
+        ),
+        builtin(
+            "Python",
+            &["pytest", "unittest", "django", "agentic"],
+            json!({
+                "python_version": {
+                    "type": "string",
+                    "description": "Python version to use"
+                },
+                "poetry_version": {
+                    "type": "string",
+                    "description": "Poetry version to install, Poetry is not installed if omitted"
+                },
+                "uv_version": {
+                    "type": "string",
+                    "description": "uv version to install, uv is not installed if omitted"
+                }
+            }),
+            &[("python_version", "3.11")],
+            &["wget", "git", "build-essential", "libffi-dev", "libssl-dev"],
+        ),
+    ]
+}
+
+fn load_languages() -> LanguageRegistry {
+    load_languages_from(&get_app_dir().join("languages"))
+}
+
+// Built-in languages followed by the custom ones found in `languages_dir`. Files that
+// fail to parse, have no template, or reuse a name already loaded are skipped with a
+// load error, so a file can't replace a built-in language.
+fn load_languages_from(languages_dir: &Path) -> LanguageRegistry {
+    let mut languages = builtin_languages();
+    let mut load_errors = Vec::new();
+
+    let mut paths: Vec<_> = match fs::read_dir(languages_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+            .collect(),
+        // No languages directory is the common case, not an error
+        Err(_) => return LanguageRegistry { languages, load_errors },
+    };
+    paths.sort();
+
+    for path in paths {
+        let definition = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<LanguageDefinition>(&content).map_err(|e| e.to_string()));
+        match definition {
+            Ok(definition) => {
+                if definition.dockerfile_template.is_none() {
+                    load_errors.push(format!("Skipped language {}: no dockerfile_template", path.display()));
+                } else if languages.iter().any(|l| l.name == definition.name) {
+                    load_errors.push(format!("Skipped language {}: '{}' is already defined", path.display(), definition.name));
+                } else {
+                    languages.push(definition);
+                }
+            }
+            Err(e) => load_errors.push(format!("Failed to load language {}: {}", path.display(), e)),
+        }
+    }
+
+    LanguageRegistry { languages, load_errors }
+}
+
+// Unknown names fall back to Javascript, like the generator always did
+pub fn get_language(name: &str) -> &'static LanguageDefinition {
+    REGISTRY
+        .languages
+        .iter()
+        .find(|l| l.name == name)
+        .or_else(|| REGISTRY.languages.iter().find(|l| l.name == DEFAULT_LANGUAGE))
+        .expect("built-in languages are always registered")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ELIXIR: &str = r#"{
+        "name": "Elixir",
+        "log_parsers": ["exunit", "agentic"],
+        "defaults": { "elixir_version": "1.16" },
+        "dockerfile_template": "FROM elixir:{{elixir_version}}\n{{checkout}}\n{{setup_repo}}\n"
+    }"#;
+
+    fn names(registry: &LanguageRegistry) -> Vec<&str> {
+        registry.languages.iter().map(|language| language.name.as_str()).collect()
+    }
+
+    #[test]
+    fn loads_custom_languages_after_the_built_ins() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("elixir.json"), ELIXIR).unwrap();
+        // Only .json files are read
+        fs::write(dir.path().join("notes.txt"), "not a language").unwrap();
+
+        let registry = load_languages_from(dir.path());
+        assert_eq!(names(&registry), ["Javascript", "Rust", "Go", "C/CPP", "Java", "Python", "Elixir"]);
+        assert!(registry.load_errors.is_empty(), "{:?}", registry.load_errors);
+        let elixir = registry.languages.last().unwrap();
+        assert_eq!(elixir.defaults["elixir_version"], "1.16");
+        assert!(elixir.docker_specs.is_empty());
+    }
+
+    #[test]
+    fn skips_files_that_cant_be_used() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a_no_template.json"), r#"{"name": "Zig", "log_parsers": ["agentic"]}"#).unwrap();
+        fs::write(dir.path().join("b_builtin.json"), ELIXIR.replace("Elixir", "Rust")).unwrap();
+        fs::write(dir.path().join("c_elixir.json"), ELIXIR).unwrap();
+        fs::write(dir.path().join("d_elixir_again.json"), ELIXIR).unwrap();
+        fs::write(dir.path().join("e_broken.json"), "{ \"name\": ").unwrap();
+
+        let registry = load_languages_from(dir.path());
+        assert_eq!(names(&registry), ["Javascript", "Rust", "Go", "C/CPP", "Java", "Python", "Elixir"]);
+        // The built-in Rust is kept
+        assert!(registry.languages[1].dockerfile_template.is_none());
+
+        let path = |name: &str| dir.path().join(name).display().to_string();
+        assert_eq!(registry.load_errors.len(), 4, "{:?}", registry.load_errors);
+        assert_eq!(registry.load_errors[0], format!("Skipped language {}: no dockerfile_template", path("a_no_template.json")));
+        assert_eq!(registry.load_errors[1], format!("Skipped language {}: 'Rust' is already defined", path("b_builtin.json")));
+        assert_eq!(registry.load_errors[2], format!("Skipped language {}: 'Elixir' is already defined", path("d_elixir_again.json")));
+        assert!(registry.load_errors[3].starts_with(&format!("Failed to load language {}: ", path("e_broken.json"))));
+    }
+
+    #[test]
+    fn a_missing_directory_only_has_the_built_ins() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = load_languages_from(&dir.path().join("languages"));
+        assert_eq!(names(&registry), ["Javascript", "Rust", "Go", "C/CPP", "Java", "Python"]);
+        assert!(registry.load_errors.is_empty());
+    }
+
+    #[test]
+    fn unknown_languages_fall_back_to_javascript() {
+        assert_eq!(get_language("Python").name, "Python");
+        assert_eq!(get_language("COBOL").name, DEFAULT_LANGUAGE);
+        assert_eq!(get_language("").name, DEFAULT_LANGUAGE);
+        // Names are matched exactly
+        assert_eq!(get_language("python").name, DEFAULT_LANGUAGE);
+    }
+}
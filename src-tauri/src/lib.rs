@@ -1,4 +1,15 @@
 mod commands;
+mod instances;
+mod json_spans;
+mod languages;
+mod log_parsers;
+mod mirrors;
+mod patches;
+mod spec_format;
+mod spec_migration;
+#[cfg(test)]
+mod test_support;
+mod verification;
 
 use tauri::Manager;
 use tauri_plugin_dialog::DialogExt;
@@ -8,14 +19,25 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|_app| {
+            // Load the built-in and custom language definitions up front
+            lazy_static::initialize(&languages::REGISTRY);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            commands::list_languages,
+            commands::import_instance,
             commands::generate_docker_file,
+            commands::convert_spec,
+            commands::export_spec_schema,
+            commands::migrate_spec,
             commands::build_docker_image,
 
             commands::stop_docker_build,
             commands::check_docker_image_exists,
             commands::run_docker_test,
             commands::stop_docker_test,
+            commands::verify_instance,
             commands::save_config,
             commands::load_config
         ])
@@ -0,0 +1,1296 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+    Error,
+}
+
+// One test as a log parser saw it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TestResult {
+    pub name: String,
+    pub status: TestStatus,
+    // Milliseconds, when the runner printed it
+    pub duration: Option<f64>,
+}
+
+impl TestResult {
+    fn new(name: &str, status: TestStatus, duration: Option<f64>) -> Self {
+        TestResult { name: name.trim().to_string(), status, duration }
+    }
+}
+
+// How many results of each status a run had
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub error: usize,
+}
+
+pub fn summarize(results: &[TestResult]) -> TestSummary {
+    let mut summary = TestSummary::default();
+    for result in results {
+        match result.status {
+            TestStatus::Passed => summary.passed += 1,
+            TestStatus::Failed => summary.failed += 1,
+            TestStatus::Skipped => summary.skipped += 1,
+            TestStatus::Error => summary.error += 1,
+        }
+    }
+    summary
+}
+
+type Parser = fn(&str) -> Vec<TestResult>;
+
+// The log_parser_name values that can be parsed here
+const PARSERS: &[(&str, Parser)] = &[
+    ("jest", parse_jest),
+    ("mocha", parse_mocha),
+    ("vitest", parse_vitest),
+    ("tap", parse_tap),
+    ("karma", parse_karma),
+    ("cargo", parse_cargo),
+    ("googletest", parse_googletest),
+    ("doctest", parse_doctest),
+    ("ctest", parse_ctest),
+    ("pytest", parse_pytest),
+];
+
+// The name of the parser that applies the spec's own `log_parser` patterns
+pub const REGEX_PARSER: &str = "regex";
+
+// `log_parser` in a spec. Each pattern is matched against every line of the log and
+// needs a `name` group, a `duration` group is read as milliseconds unless it has a unit.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegexParserSpec {
+    pub passed: Option<String>,
+    pub failed: Option<String>,
+    pub skipped: Option<String>,
+    // Applied to every captured name, e.g. to drop a file prefix the dataset doesn't have
+    pub name_normalizer: Option<NameNormalizer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NameNormalizer {
+    pub pattern: String,
+    // `$1` and `${group}` refer to the pattern's groups
+    #[serde(default)]
+    pub replacement: String,
+}
+
+pub struct RegexParser {
+    patterns: Vec<(TestStatus, Regex)>,
+    normalizer: Option<(Regex, String)>,
+}
+
+impl RegexParser {
+    // Errors come with the JSON pointer of the pattern at fault
+    pub fn compile(spec: &RegexParserSpec) -> Result<Self, (String, String)> {
+        let mut patterns = Vec::new();
+        for (key, status, pattern) in [
+            ("passed", TestStatus::Passed, &spec.passed),
+            ("failed", TestStatus::Failed, &spec.failed),
+            ("skipped", TestStatus::Skipped, &spec.skipped),
+        ] {
+            let Some(pattern) = pattern else { continue };
+            let pointer = format!("/log_parser/{}", key);
+            let regex = Regex::new(pattern).map_err(|e| (pointer.clone(), format!("Invalid {} pattern: {}", key, e)))?;
+            if !regex.capture_names().any(|name| name == Some("name")) {
+                return Err((pointer, format!("The {} pattern needs a (?P<name>...) group for the test name", key)));
+            }
+            patterns.push((status, regex));
+        }
+        if patterns.is_empty() {
+            return Err(("/log_parser".to_string(), "log_parser needs at least one of passed, failed or skipped".to_string()));
+        }
+
+        let normalizer = match &spec.name_normalizer {
+            Some(normalizer) => {
+                let regex = Regex::new(&normalizer.pattern).map_err(|e| {
+                    ("/log_parser/name_normalizer/pattern".to_string(), format!("Invalid name_normalizer pattern: {}", e))
+                })?;
+                Some((regex, normalizer.replacement.clone()))
+            }
+            None => None,
+        };
+        Ok(RegexParser { patterns, normalizer })
+    }
+
+    // The first pattern that matches a line decides its status, and a test's last
+    // result is the one that counts
+    fn parse(&self, log: &str) -> Vec<TestResult> {
+        let mut results: Vec<TestResult> = Vec::new();
+        for line in log.lines() {
+            let Some((status, captures)) = self
+                .patterns
+                .iter()
+                .find_map(|(status, regex)| regex.captures(line).map(|captures| (*status, captures)))
+            else {
+                continue;
+            };
+            let Some(name) = captures.name("name").map(|name| name.as_str()) else {
+                continue;
+            };
+            let name = match &self.normalizer {
+                Some((regex, replacement)) => regex.replace_all(name, replacement.as_str()).to_string(),
+                None => name.to_string(),
+            };
+            if name.trim().is_empty() {
+                continue;
+            }
+            let duration = captures.name("duration").and_then(|duration| {
+                let duration = duration.as_str().trim();
+                duration.parse::<f64>().ok().or_else(|| parse_duration(duration))
+            });
+            let result = TestResult::new(&name, status, duration);
+            match results.iter_mut().find(|existing| existing.name == result.name) {
+                Some(existing) => *existing = result,
+                None => results.push(result),
+            }
+        }
+        results
+    }
+}
+
+// The name of the parser that hands the log to an external script
+pub const AGENTIC_PARSER: &str = "agentic";
+
+// How long a parser script gets before it's killed
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+// A parser picked by a spec's log_parser_name
+pub enum LogParser {
+    Builtin(Parser),
+    Regex(RegexParser),
+    // An executable that reads the log on stdin and prints a JSON list of results
+    Script(PathBuf),
+}
+
+impl LogParser {
+    pub fn new(parser_name: &str, regex_spec: Option<&RegexParserSpec>, script: Option<&str>) -> Result<Self, String> {
+        if parser_name == REGEX_PARSER {
+            let spec = regex_spec.ok_or("The regex log parser needs a log_parser object in the spec")?;
+            return RegexParser::compile(spec).map(LogParser::Regex).map_err(|(_, message)| message);
+        }
+        if parser_name == AGENTIC_PARSER {
+            let script = script
+                .map(str::trim)
+                .filter(|script| !script.is_empty())
+                .ok_or("The agentic log parser needs a parser script, set log_parser_script in the spec or in the settings")?;
+            return Ok(LogParser::Script(PathBuf::from(script)));
+        }
+        PARSERS
+            .iter()
+            .find(|(name, _)| *name == parser_name)
+            .map(|(_, parser)| LogParser::Builtin(*parser))
+            .ok_or_else(|| {
+                format!(
+                    "The {} log parser can't be run by the app yet, use \"regex\" or \"agentic\" for this runner",
+                    parser_name
+                )
+            })
+    }
+
+    // Turn the output of a test run into per-test results. Only a script can fail.
+    pub async fn parse(&self, log: &str) -> Result<Vec<TestResult>, String> {
+        match self {
+            LogParser::Builtin(parser) => Ok(parser(&strip_ansi(log))),
+            LogParser::Regex(parser) => Ok(parser.parse(&strip_ansi(log))),
+            // Scripts get the log as the runner printed it
+            LogParser::Script(script) => run_parser_script(script, log, SCRIPT_TIMEOUT).await,
+        }
+    }
+}
+
+// Run a parser script with the log on stdin. It has to exit successfully and print a
+// JSON list of `{"name", "status", "duration"}` objects, see the README.
+async fn run_parser_script(script: &Path, log: &str, timeout: Duration) -> Result<Vec<TestResult>, String> {
+    let mut child = Command::new(script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start the parser script {}: {}", script.display(), e))?;
+
+    // Write from another task so a script that prints before it has read everything
+    // can't block on a full pipe. stdin closes when the write is done.
+    let mut stdin = child.stdin.take().ok_or("Failed to open the parser script's stdin")?;
+    let log = log.to_string();
+    tokio::spawn(async move {
+        let _ = stdin.write_all(log.as_bytes()).await;
+    });
+
+    // Dropping the child on a timeout kills it
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| format!("The parser script didn't finish within {} seconds and was stopped", timeout.as_secs()))?
+        .map_err(|e| format!("Failed to run the parser script: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "The parser script exited with code {}: {}",
+            output.status.code().unwrap_or(-1),
+            stderr.trim()
+        ));
+    }
+
+    let results: Vec<TestResult> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("The parser script's output is not a JSON list of test results: {}", e))?;
+    Ok(results
+        .into_iter()
+        .filter(|result| !result.name.trim().is_empty())
+        .map(|result| TestResult::new(&result.name, result.status, result.duration))
+        .collect())
+}
+
+// Runners color their output when they think they're on a terminal, which some are
+// told to be in CI
+fn strip_ansi(log: &str) -> String {
+    let mut stripped = String::with_capacity(log.len());
+    let mut chars = log.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            stripped.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_some() {
+            // CSI sequences end with a byte in @..~
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    stripped
+}
+
+// "5ms", "5 ms", "1.5s" or "1.5 s" in milliseconds
+fn parse_duration(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (number, scale) = if let Some(number) = text.strip_suffix("ms") {
+        (number, 1.0)
+    } else if let Some(number) = text.strip_suffix('s') {
+        (number, 1000.0)
+    } else {
+        return None;
+    };
+    number.trim().parse::<f64>().ok().map(|number| number * scale)
+}
+
+// Split a trailing "(5 ms)" off a test title
+fn split_parenthesized_duration(text: &str) -> (&str, Option<f64>) {
+    if let Some(open) = text.strip_suffix(')').and_then(|rest| rest.rfind(" (")) {
+        if let Some(duration) = parse_duration(&text[open + 2..text.len() - 1]) {
+            return (&text[..open], Some(duration));
+        }
+    }
+    (text, None)
+}
+
+// Split a trailing " 5ms" off a test title
+fn split_trailing_duration(text: &str) -> (&str, Option<f64>) {
+    if let Some((name, last)) = text.rsplit_once(' ') {
+        if let Some(duration) = parse_duration(last) {
+            return (name, Some(duration));
+        }
+    }
+    (text, None)
+}
+
+// The status a leading check mark or cross stands for, and the rest of the line
+fn split_marker<'a>(line: &'a str, markers: &[(&str, TestStatus)]) -> Option<(TestStatus, &'a str)> {
+    let line = line.trim_start();
+    markers.iter().find_map(|(marker, status)| {
+        line.strip_prefix(marker)
+            .and_then(|rest| rest.strip_prefix(' '))
+            .map(|rest| (*status, rest))
+    })
+}
+
+// Jest's verbose listing, `✓ title (5 ms)` per test, without the describe blocks, which
+// is how SWE-bench names Jest tests
+fn parse_jest(log: &str) -> Vec<TestResult> {
+    const MARKERS: &[(&str, TestStatus)] = &[
+        ("✓", TestStatus::Passed),
+        ("√", TestStatus::Passed),
+        ("✕", TestStatus::Failed),
+        ("×", TestStatus::Failed),
+        ("○", TestStatus::Skipped),
+        ("✎", TestStatus::Skipped),
+    ];
+    log.lines()
+        .filter_map(|line| split_marker(line, MARKERS))
+        .map(|(status, rest)| {
+            let rest = rest.strip_prefix("skipped ").or_else(|| rest.strip_prefix("todo ")).unwrap_or(rest);
+            let (name, duration) = split_parenthesized_duration(rest.trim_end());
+            TestResult::new(name, status, duration)
+        })
+        .collect()
+}
+
+// Mocha's spec reporter. Failures are numbered in the listing, `1) title`, and listed
+// again with their errors after the "N passing" summary, where they're skipped.
+fn parse_mocha(log: &str) -> Vec<TestResult> {
+    const MARKERS: &[(&str, TestStatus)] = &[
+        ("✓", TestStatus::Passed),
+        ("✔", TestStatus::Passed),
+        ("√", TestStatus::Passed),
+        ("-", TestStatus::Skipped),
+    ];
+    let mut results = Vec::new();
+    let mut in_failure_details = false;
+    for line in log.lines() {
+        // The listing is always indented, which keeps other `- ` lines out
+        if !line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let trimmed = line.trim();
+        if is_mocha_summary(trimmed) {
+            in_failure_details = true;
+        } else if let Some((status, rest)) = split_marker(line, MARKERS) {
+            // Another mocha run sharing the log
+            in_failure_details = false;
+            let (name, duration) = split_parenthesized_duration(rest.trim_end());
+            results.push(TestResult::new(name, status, duration));
+        } else if let Some((number, name)) = trimmed.split_once(") ") {
+            if !in_failure_details && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+                results.push(TestResult::new(name, TestStatus::Failed, None));
+            }
+        }
+    }
+    results
+}
+
+fn is_mocha_summary(line: &str) -> bool {
+    line.split_once(' ').is_some_and(|(count, rest)| {
+        !count.is_empty()
+            && count.chars().all(|c| c.is_ascii_digit())
+            && ["passing", "failing", "pending"].iter().any(|word| rest.starts_with(word))
+    })
+}
+
+// Vitest's default and verbose reporters, `✓ file > suite > title 5ms` per test. The
+// per-file lines, `✓ file (3 tests) 12ms`, aren't tests.
+fn parse_vitest(log: &str) -> Vec<TestResult> {
+    const MARKERS: &[(&str, TestStatus)] = &[
+        ("✓", TestStatus::Passed),
+        ("√", TestStatus::Passed),
+        ("×", TestStatus::Failed),
+        ("✗", TestStatus::Failed),
+        ("↓", TestStatus::Skipped),
+    ];
+    log.lines()
+        .filter_map(|line| split_marker(line, MARKERS))
+        .filter(|(_, rest)| !is_vitest_file_line(rest))
+        .map(|(status, rest)| {
+            let rest = rest.trim_end();
+            let rest = rest.strip_suffix(" [skipped]").unwrap_or(rest);
+            let (name, duration) = split_trailing_duration(rest);
+            TestResult::new(name, status, duration)
+        })
+        .collect()
+}
+
+fn is_vitest_file_line(rest: &str) -> bool {
+    rest.split_once(" (").is_some_and(|(_, counts)| {
+        let count = counts.split(' ').next().unwrap_or("");
+        !count.is_empty()
+            && count.chars().all(|c| c.is_ascii_digit())
+            && (counts[count.len()..].starts_with(" test") || counts[count.len()..].starts_with(" suite"))
+    })
+}
+
+// TAP 12 to 14, with subtests. `ok 1 - title # SKIP`, where TODO tests don't count as
+// failures, and durations come from node's `duration_ms` YAML diagnostics.
+fn parse_tap(log: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    // The test a YAML block after it belongs to
+    let mut last_test: Option<usize> = None;
+    for line in log.lines() {
+        let trimmed = line.trim();
+        let (ok, rest) = if let Some(rest) = trimmed.strip_prefix("not ok") {
+            (false, rest)
+        } else if let Some(rest) = trimmed.strip_prefix("ok") {
+            (true, rest)
+        } else {
+            if let (Some(index), Some(duration)) = (last_test, trimmed.strip_prefix("duration_ms:")) {
+                results[index].duration = duration.trim().parse().ok();
+            }
+            continue;
+        };
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            continue;
+        }
+
+        let rest = rest.trim_start();
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+        let rest = rest.strip_prefix("- ").unwrap_or(rest);
+        let (name, directive) = match rest.split_once(" # ") {
+            Some((name, directive)) => (name, directive.trim().to_uppercase()),
+            None => (rest.strip_suffix(" #").unwrap_or(rest), String::new()),
+        };
+        let status = if directive.starts_with("SKIP") || directive.starts_with("TODO") {
+            TestStatus::Skipped
+        } else if ok {
+            TestStatus::Passed
+        } else {
+            TestStatus::Failed
+        };
+        results.push(TestResult::new(&unescape_tap(name), status, None));
+        last_test = Some(results.len() - 1);
+    }
+    results
+}
+
+fn unescape_tap(name: &str) -> String {
+    name.replace("\\#", "#").replace("\\\\", "\\")
+}
+
+// Karma with karma-mocha-reporter or karma-spec-reporter, where suites are indented
+// titles and tests carry a mark. Names are the suites and the title joined with " > ",
+// like SWE-bench does. The progress reporter only names failures, as
+// `Browser (OS) suite title FAILED`.
+fn parse_karma(log: &str) -> Vec<TestResult> {
+    const MARKERS: &[(&str, TestStatus)] = &[
+        ("✔", TestStatus::Passed),
+        ("✓", TestStatus::Passed),
+        ("✖", TestStatus::Failed),
+        ("✗", TestStatus::Failed),
+        ("×", TestStatus::Failed),
+        ("-", TestStatus::Skipped),
+    ];
+    let mut results = Vec::new();
+    let mut suites: Vec<(usize, String)> = Vec::new();
+    // The mocha reporter lists the failures again after its summary
+    let mut in_summary = false;
+    for line in log.lines() {
+        let trimmed = line.trim();
+        if line.starts_with("SUMMARY:") {
+            in_summary = true;
+        } else if line.starts_with("START:") {
+            in_summary = false;
+        }
+        if in_summary {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if let Some((status, rest)) = split_marker(line, MARKERS) {
+            suites.retain(|(suite_indent, _)| *suite_indent < indent);
+            let (title, status) = match rest.trim_end().strip_suffix(" SKIPPED") {
+                Some(title) => (title, TestStatus::Skipped),
+                None => (rest.trim_end(), status),
+            };
+            let (title, duration) = split_parenthesized_duration(title);
+            let name = suites
+                .iter()
+                .map(|(_, suite)| suite.as_str())
+                .chain(std::iter::once(title.trim()))
+                .collect::<Vec<_>>()
+                .join(" > ");
+            results.push(TestResult::new(&name, status, duration));
+        } else if let Some(rest) = trimmed.strip_suffix(" FAILED") {
+            // `Chrome Headless 120.0 (Linux x86_64) suite title FAILED`
+            if let Some((_, name)) = rest.split_once(") ") {
+                results.push(TestResult::new(name, TestStatus::Failed, None));
+            }
+        } else if indent == 0 {
+            suites.clear();
+        } else if is_suite_title(trimmed) {
+            suites.retain(|(suite_indent, _)| *suite_indent < indent);
+            suites.push((indent, trimmed.to_string()));
+        }
+    }
+    results
+}
+
+// Indented lines between tests are suite titles, unless they look like part of an error
+fn is_suite_title(line: &str) -> bool {
+    !line.is_empty() && !line.starts_with("at ") && !line.contains("Error") && !line.starts_with("Expected ")
+}
+
+// cargo test in any of its shapes, which can be mixed in one log:
+// - libtest's text output, `test foo::bar ... ok`, from every test binary and the doctests
+// - cargo-nextest's `PASS [   0.004s] crate::binary foo::bar` lines
+// - libtest's `--format json` events
+// A test whose thread panicked but never got a result, because the binary crashed or
+// was killed, counts as failed.
+fn parse_cargo(log: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    // With --nocapture a test's output can land between `test foo ... ` and its result
+    let mut pending: Vec<String> = Vec::new();
+    let mut panicked: Vec<String> = Vec::new();
+    for line in log.lines() {
+        let trimmed = line.trim();
+        if let Some(result) = parse_libtest_json(trimmed).or_else(|| parse_nextest(trimmed)) {
+            record_result(&mut results, result);
+        } else if let Some(rest) = trimmed.strip_prefix("test ") {
+            let Some((name, status)) = rest.split_once(" ... ") else {
+                if let Some(name) = rest.strip_suffix(" ...") {
+                    pending.push(name.to_string());
+                }
+                continue;
+            };
+            match libtest_status(status) {
+                Some((status, duration)) => record_result(&mut results, TestResult::new(name, status, duration)),
+                None => pending.push(name.to_string()),
+            }
+        } else if let Some((name, _)) = trimmed.strip_prefix("thread '").and_then(|rest| rest.split_once("' panicked")) {
+            // Test threads are named after their test
+            if name != "main" && name != "<unnamed>" {
+                panicked.push(name.to_string());
+            }
+        } else if let Some((status, duration)) = libtest_status(trimmed).filter(|_| !pending.is_empty()) {
+            let name = pending.remove(0);
+            record_result(&mut results, TestResult::new(&name, status, duration));
+        }
+    }
+
+    for name in pending.into_iter().chain(panicked) {
+        if !results.iter().any(|result| result.name == name) {
+            results.push(TestResult::new(&name, TestStatus::Failed, None));
+        }
+    }
+    results
+}
+
+// The last result for a name is the one that counts. nextest prints failures again in
+// its summary and retries flaky tests, pytest -v -rA lists every test twice.
+fn record_result(results: &mut Vec<TestResult>, result: TestResult) {
+    match results.iter_mut().find(|existing| existing.name == result.name) {
+        Some(existing) => *existing = result,
+        None => results.push(result),
+    }
+}
+
+// `ok`, `FAILED`, `ignored` or `ignored, reason`, with `<0.001s>` from --report-time
+fn libtest_status(text: &str) -> Option<(TestStatus, Option<f64>)> {
+    let (status, duration) = match text.trim().split_once(" <") {
+        Some((status, time)) => (status, time.strip_suffix('>').and_then(parse_duration)),
+        None => (text.trim(), None),
+    };
+    let status = match status {
+        "ok" | "bench" => TestStatus::Passed,
+        "FAILED" => TestStatus::Failed,
+        status if status == "ignored" || status.starts_with("ignored, ") => TestStatus::Skipped,
+        _ => return None,
+    };
+    Some((status, duration))
+}
+
+// `PASS [   0.004s] crate::binary foo::bar`, where retries show up as `TRY 2 PASS`
+fn parse_nextest(line: &str) -> Option<TestResult> {
+    let line = match line.strip_prefix("TRY ") {
+        Some(retry) => retry.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start(),
+        None => line,
+    };
+    let (status, rest) = line.split_once(" [")?;
+    let (time, test) = rest.split_once(']')?;
+    let status = match status.trim() {
+        "PASS" | "LEAK" => TestStatus::Passed,
+        status if status.starts_with("FLAKY") => TestStatus::Passed,
+        "FAIL" | "LEAK-FAIL" => TestStatus::Failed,
+        "SKIP" => TestStatus::Skipped,
+        "TIMEOUT" | "ABORT" => TestStatus::Error,
+        status if status.starts_with("SIG") && status.chars().all(|c| c.is_ascii_uppercase()) => TestStatus::Error,
+        _ => return None,
+    };
+    // The binary id comes first, test names have no spaces
+    let name = test.split_whitespace().last()?;
+    Some(TestResult::new(name, status, parse_duration(time)))
+}
+
+// `{ "type": "test", "event": "ok", "name": "foo::bar", "exec_time": 0.001 }`
+fn parse_libtest_json(line: &str) -> Option<TestResult> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    let event: serde_json::Value = serde_json::from_str(line).ok()?;
+    if event.get("type")?.as_str()? != "test" {
+        return None;
+    }
+    let status = match event.get("event")?.as_str()? {
+        "ok" => TestStatus::Passed,
+        "failed" => TestStatus::Failed,
+        "ignored" => TestStatus::Skipped,
+        _ => return None,
+    };
+    let duration = event.get("exec_time").and_then(|time| time.as_f64()).map(|seconds| seconds * 1000.0);
+    Some(TestResult::new(event.get("name")?.as_str()?, status, duration))
+}
+
+// GoogleTest's `[ RUN      ]` and `[       OK ]` markers. Parameterised and typed tests
+// keep their instantiation in the name, `Inst/Suite.Test/0`, without the `, where
+// GetParam() = 4` note. A test that was started but never finished crashed its binary.
+fn parse_googletest(log: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    let mut running: Vec<String> = Vec::new();
+    for line in log.lines() {
+        let Some((marker, rest)) = line.trim().strip_prefix('[').and_then(|rest| rest.split_once(']')) else {
+            continue;
+        };
+        let (rest, duration) = split_parenthesized_duration(rest.trim());
+        let name = rest.split_once(", where ").map(|(name, _)| name).unwrap_or(rest).trim();
+        // The summary has `[  FAILED  ] 1 test, listed below:` and the like
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            continue;
+        }
+        let status = match marker.trim() {
+            "RUN" => {
+                running.push(name.to_string());
+                continue;
+            }
+            "OK" => TestStatus::Passed,
+            "FAILED" => TestStatus::Failed,
+            "SKIPPED" => TestStatus::Skipped,
+            _ => continue,
+        };
+        running.retain(|running| running != name);
+        // The summary lists the failures again, without durations
+        match results.iter_mut().find(|result| result.name == name) {
+            Some(result) => result.status = status,
+            None => results.push(TestResult::new(name, status, duration)),
+        }
+    }
+    for name in running {
+        results.push(TestResult::new(&name, TestStatus::Failed, None));
+    }
+    results
+}
+
+// doctest prints a `TEST CASE:` block per failing case, and per passing case with
+// `--success`. `--duration` adds a `0.000012 s: name` line for every case, which is
+// the only way passing cases show up otherwise.
+fn parse_doctest(log: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    // The case whose block is being read, and whether it has failed so far
+    let mut current: Option<(String, bool)> = None;
+    let finish = |results: &mut Vec<TestResult>, current: &mut Option<(String, bool)>| {
+        if let Some((name, failed)) = current.take() {
+            let status = if failed { TestStatus::Failed } else { TestStatus::Passed };
+            match results.iter_mut().find(|result| result.name == name) {
+                // A case's subcases each get a block, any failure fails it
+                Some(result) => {
+                    if failed {
+                        result.status = status;
+                    }
+                }
+                None => results.push(TestResult::new(&name, status, None)),
+            }
+        }
+    };
+    for line in log.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("TEST CASE:") {
+            finish(&mut results, &mut current);
+            current = Some((name.trim().to_string(), false));
+        } else if trimmed.starts_with("=====") || trimmed.starts_with("[doctest]") {
+            finish(&mut results, &mut current);
+        } else if let Some((seconds, name)) = trimmed
+            .split_once(" s: ")
+            .and_then(|(seconds, name)| seconds.parse::<f64>().ok().map(|seconds| (seconds, name)))
+        {
+            match results.iter_mut().find(|result| result.name == name.trim()) {
+                Some(result) => result.duration = Some(seconds * 1000.0),
+                None => results.push(TestResult::new(name, TestStatus::Passed, Some(seconds * 1000.0))),
+            }
+        } else if let Some((_, failed)) = current.as_mut() {
+            if trimmed.contains("ERROR:") || trimmed.contains("TEST CASE FAILED") || trimmed.contains("CRASHED") {
+                *failed = true;
+            }
+        }
+    }
+    finish(&mut results, &mut current);
+    results
+}
+
+// CTest's `1/3 Test #1: name ........   Passed    0.01 sec` lines
+fn parse_ctest(log: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    for line in log.lines() {
+        let Some((_, rest)) = line.split_once("Test #") else {
+            continue;
+        };
+        let Some((number, rest)) = rest.split_once(": ") else {
+            continue;
+        };
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        // The name is padded with dots, long names get at least a few
+        let Some(dots) = rest.find(" .").or_else(|| rest.find("***")) else {
+            continue;
+        };
+        let name = &rest[..dots];
+        let outcome = rest[dots..].trim_start_matches([' ', '.', '*']);
+        let (outcome, duration) = match outcome.trim_end().strip_suffix(" sec") {
+            Some(outcome) => match outcome.trim_end().rsplit_once(char::is_whitespace) {
+                Some((outcome, seconds)) => (outcome.trim(), seconds.parse::<f64>().ok().map(|seconds| seconds * 1000.0)),
+                None => (outcome.trim(), None),
+            },
+            None => (outcome.trim(), None),
+        };
+        let status = if outcome == "Passed" {
+            TestStatus::Passed
+        } else if outcome.starts_with("Not Run") || outcome == "Skipped" || outcome.starts_with("Disabled") {
+            TestStatus::Skipped
+        } else if outcome.starts_with("Failed") {
+            TestStatus::Failed
+        } else if outcome.starts_with("Timeout") || outcome.starts_with("Exception") || outcome.starts_with("Child aborted") {
+            TestStatus::Error
+        } else {
+            continue;
+        };
+        results.push(TestResult::new(name, status, duration));
+    }
+    results
+}
+
+// pytest's `-rA` short summary, `PASSED tests/test_a.py::test_b`, and its `-v` listing,
+// `tests/test_a.py::test_b PASSED [ 50%]`. Expected failures count as passed and
+// unexpected passes as failed, like the SWE-bench harness grades them.
+fn parse_pytest(log: &str) -> Vec<TestResult> {
+    const STATUSES: &[(&str, TestStatus)] = &[
+        ("PASSED", TestStatus::Passed),
+        ("XFAIL", TestStatus::Passed),
+        ("FAILED", TestStatus::Failed),
+        ("XPASS", TestStatus::Failed),
+        ("SKIPPED", TestStatus::Skipped),
+        ("ERROR", TestStatus::Error),
+    ];
+    let mut results = Vec::new();
+    for line in log.lines() {
+        // pytest-xdist puts `[gw0] [ 50%]` in front of its lines
+        let mut line = line.trim();
+        while let Some(rest) = line.strip_prefix('[').and_then(|rest| rest.split_once("] ")) {
+            line = rest.1.trim_start();
+        }
+
+        let summary = STATUSES.iter().find_map(|(word, status)| {
+            line.strip_prefix(word).and_then(|rest| rest.strip_prefix(' ')).map(|rest| (*status, rest))
+        });
+        if let Some((status, rest)) = summary {
+            // `SKIPPED [1] tests/test_a.py:12: reason` only has the location of the skip
+            if rest.starts_with('[') {
+                continue;
+            }
+            let name = rest.split_once(" - ").map(|(name, _)| name).unwrap_or(rest);
+            if !name.trim().is_empty() {
+                record_result(&mut results, TestResult::new(name, status, None));
+            }
+            continue;
+        }
+
+        // `-v` lines are `<node id> <STATUS>`, then maybe a reason and the progress
+        let Some((name, rest)) = pytest_node_id(line) else { continue };
+        let verbose = STATUSES.iter().find_map(|(word, status)| {
+            let after = rest.strip_prefix(word)?;
+            (after.is_empty() || after.starts_with(' ')).then_some((*status, after.trim()))
+        });
+        if let Some((status, after)) = verbose {
+            let after = match after.rsplit_once('[') {
+                Some((before, progress)) if is_pytest_progress(progress) => before.trim_end(),
+                _ => after,
+            };
+            if after.is_empty() || (after.starts_with('(') && after.ends_with(')')) {
+                record_result(&mut results, TestResult::new(name, status, None));
+            }
+        }
+    }
+    results
+}
+
+// Splits `tests/test_a.py::test_b[x y] PASSED ...` after the node id: a path without
+// spaces, `::` and the test's name, where only its parameters can contain spaces
+fn pytest_node_id(line: &str) -> Option<(&str, &str)> {
+    let (path, _) = line.split_once("::")?;
+    if path.is_empty() || path.contains(char::is_whitespace) || !path.contains(".py") {
+        return None;
+    }
+    let mut depth = 0usize;
+    for (index, c) in line.char_indices().skip(path.len() + 2) {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                return (index > path.len() + 2).then(|| (&line[..index], &line[index + 1..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// ` 42%]`, what's left of the `[ 42%]` progress after its opening bracket
+fn is_pytest_progress(progress: &str) -> bool {
+    progress
+        .strip_suffix("%]")
+        .map(|percent| {
+            let percent = percent.trim_start();
+            !percent.is_empty() && percent.chars().all(|c| c.is_ascii_digit())
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TestStatus::*;
+
+    #[track_caller]
+    fn assert_results(parser: Parser, log: &str, expected: &[(&str, TestStatus, Option<f64>)]) {
+        let results: Vec<(String, TestStatus, Option<f64>)> =
+            parser(&strip_ansi(log)).into_iter().map(|result| (result.name, result.status, result.duration)).collect();
+        let expected: Vec<(String, TestStatus, Option<f64>)> =
+            expected.iter().map(|(name, status, duration)| (name.to_string(), *status, *duration)).collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn jest() {
+        assert_results(
+            parse_jest,
+            include_str!("../tests/fixtures/logs/jest.log"),
+            &[
+                ("adds numbers", Passed, Some(3.0)),
+                ("subtracts numbers", Failed, Some(12.0)),
+                ("multiplies", Skipped, None),
+                ("divides", Skipped, None),
+                ("handles (weird) names", Passed, None),
+                ("colored test", Passed, Some(1.0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn mocha() {
+        // The failure details after the summary repeat `1) ...` and must not add tests
+        assert_results(
+            parse_mocha,
+            include_str!("../tests/fixtures/logs/mocha.log"),
+            &[
+                ("should return -1 when not present", Passed, Some(5.0)),
+                ("should return index", Failed, None),
+                ("pending test", Skipped, None),
+                ("another", Passed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn vitest() {
+        // `✓ src/a.test.ts (3 tests)` is a file, not a test
+        assert_results(
+            parse_vitest,
+            include_str!("../tests/fixtures/logs/vitest.log"),
+            &[
+                ("b > fails", Failed, Some(3.0)),
+                ("b > works", Passed, Some(1.0)),
+                ("src/c.test.ts > skipped", Skipped, None),
+                ("src/d.test.ts > suite > slow one", Passed, Some(1200.0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn tap() {
+        assert_results(
+            parse_tap,
+            include_str!("../tests/fixtures/logs/tap.log"),
+            &[
+                ("inner passes", Passed, Some(1.5)),
+                ("inner fails", Skipped, None),
+                ("skipped one", Skipped, None),
+                ("suite", Failed, Some(4.25)),
+                ("has # hash", Passed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn karma() {
+        // The tests under SUMMARY: and FAILED TESTS: are repeats
+        assert_results(
+            parse_karma,
+            include_str!("../tests/fixtures/logs/karma.log"),
+            &[
+                ("Calculator > add > adds", Passed, None),
+                ("Calculator > add > adds negatives", Failed, None),
+                ("Calculator > subtracts", Passed, None),
+                ("Other > skipped thing", Skipped, None),
+                ("Legacy suite does stuff", Failed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn cargo_test_binaries() {
+        // Like the SWE-bench harness, a name two binaries share keeps the last result
+        assert_results(
+            parse_cargo,
+            include_str!("../tests/fixtures/logs/cargo_binaries.log"),
+            &[
+                ("tests::adds", Failed, None),
+                ("tests::slow", Skipped, None),
+                ("tests::noisy", Passed, None),
+                ("tests::timed", Passed, Some(12.0)),
+                ("cli::parses_args", Failed, None),
+                ("it_works", Passed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn cargo_doctests() {
+        assert_results(
+            parse_cargo,
+            include_str!("../tests/fixtures/logs/cargo_doctests.log"),
+            &[
+                ("src/lib.rs - add (line 5)", Passed, None),
+                ("src/lib.rs - Calculator::sub (line 12) - compile fail", Failed, None),
+                ("src/lib.rs - Calculator (line 20) - compile", Passed, None),
+                ("src/lib.rs - net::fetch (line 31)", Skipped, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn cargo_panics_without_a_result() {
+        // The binary crashed, main and unnamed threads aren't tests
+        assert_results(
+            parse_cargo,
+            include_str!("../tests/fixtures/logs/cargo_panics.log"),
+            &[
+                ("tests::first", Passed, None),
+                ("tests::hangs", Failed, None),
+                ("tests::crashes", Failed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn nextest() {
+        assert_results(
+            parse_cargo,
+            include_str!("../tests/fixtures/logs/nextest.log"),
+            &[
+                ("tests::nx_pass", Passed, Some(4.0)),
+                ("tests::nx_fail", Failed, Some(10.0)),
+                ("tests::nx_flaky", Passed, Some(11.0)),
+                ("tests::nx_skip", Skipped, Some(0.0)),
+                ("tests::nx_timeout", Error, Some(60001.0)),
+                ("tests::nx_segv", Error, Some(100.0)),
+                ("it_aborts", Error, Some(50.0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn libtest_json() {
+        // The timeout event only warns that a test is slow, its result comes later
+        assert_results(
+            parse_cargo,
+            include_str!("../tests/fixtures/logs/libtest_json.log"),
+            &[
+                ("json::a", Passed, Some(2.0)),
+                ("json::b", Failed, None),
+                ("json::c", Skipped, None),
+                ("json::d", Passed, Some(61500.0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn googletest() {
+        // The `, where ...` suffix is dropped, Crash.Test started but never finished
+        assert_results(
+            parse_googletest,
+            include_str!("../tests/fixtures/logs/googletest.log"),
+            &[
+                ("MathTest.Adds", Passed, Some(0.0)),
+                ("MathTest.Fails", Failed, Some(1.0)),
+                ("MathTest.Skipped", Skipped, Some(0.0)),
+                ("Inst/ParamTest.Works/0", Passed, Some(0.0)),
+                ("Inst/ParamTest.Works/1", Failed, Some(0.0)),
+                ("TypedTest/0.Works", Passed, Some(2.0)),
+                ("TypedTest/1.Works", Failed, Some(1.0)),
+                ("Crash.Test", Failed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn doctest() {
+        // A failing subcase fails its test case, which keeps its own name
+        assert_results(
+            parse_doctest,
+            include_str!("../tests/fixtures/logs/doctest.log"),
+            &[
+                ("passes quickly", Passed, Some(0.01)),
+                ("addition fails", Failed, Some(0.5)),
+                ("shown with success", Passed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn ctest() {
+        assert_results(
+            parse_ctest,
+            include_str!("../tests/fixtures/logs/ctest.log"),
+            &[
+                ("unit_tests", Passed, Some(10.0)),
+                ("failing", Failed, Some(0.0)),
+                ("timeout", Error, Some(1500.0)),
+                ("disabled", Skipped, Some(0.0)),
+                ("skip_rc", Skipped, Some(0.0)),
+                ("crash", Error, Some(10.0)),
+                ("needs_data", Skipped, Some(0.0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn pytest() {
+        assert_results(
+            parse_pytest,
+            include_str!("../tests/fixtures/logs/pytest.log"),
+            &[
+                ("tests/test_api.py::test_get", Passed, None),
+                ("tests/test_api.py::test_post", Failed, None),
+                ("tests/test_api.py::test_param[a b-1]", Passed, None),
+                ("tests/test_api.py::test_skip", Skipped, None),
+                ("tests/test_api.py::test_xfail", Passed, None),
+                ("tests/test_api.py::test_xpass", Failed, None),
+                ("tests/test_api.py::test_error", Error, None),
+                ("tests/test_other.py::test_parallel", Passed, None),
+                ("tests/test_broken.py", Error, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn pytest_verbose_lines_start_with_the_node_id() {
+        assert_results(
+            parse_pytest,
+            "tests/a.py::test_one PASSED
+tests/a.py::TestClass::test_two[x - y] FAILED                        [ 50%]
+tests/a.py::test_three SKIPPED (needs a GPU)    [100%]
+tests/a.py::test_four PASSED but not really
+  tests/a.py::test_five PASSED
+E   ValueError: mod::thing ERROR happened
+assert mod.py::thing FAILED
+tests/a.py::test_six[1]FAILED
+tests/a.py:: PASSED
+",
+            &[
+                ("tests/a.py::test_one", Passed, None),
+                ("tests/a.py::TestClass::test_two[x - y]", Failed, None),
+                ("tests/a.py::test_three", Skipped, None),
+                ("tests/a.py::test_five", Passed, None),
+            ],
+        );
+    }
+
+    fn regex_spec(passed: Option<&str>, failed: Option<&str>, skipped: Option<&str>) -> RegexParserSpec {
+        RegexParserSpec {
+            passed: passed.map(str::to_string),
+            failed: failed.map(str::to_string),
+            skipped: skipped.map(str::to_string),
+            name_normalizer: None,
+        }
+    }
+
+    #[track_caller]
+    fn assert_regex_results(spec: &RegexParserSpec, log: &str, expected: &[(&str, TestStatus, Option<f64>)]) {
+        let parser = RegexParser::compile(spec).unwrap_or_else(|(_, message)| panic!("{}", message));
+        let results: Vec<(String, TestStatus, Option<f64>)> =
+            parser.parse(log).into_iter().map(|result| (result.name, result.status, result.duration)).collect();
+        let expected: Vec<(String, TestStatus, Option<f64>)> =
+            expected.iter().map(|(name, status, duration)| (name.to_string(), *status, *duration)).collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn regex_parser_rejects_bad_patterns() {
+        let error = |spec: RegexParserSpec| RegexParser::compile(&spec).err().unwrap();
+
+        let (pointer, message) = error(regex_spec(Some("^ok (?P<name>\\S+)"), Some("^FAIL (?P<name>[a-z"), None));
+        assert_eq!(pointer, "/log_parser/failed");
+        assert!(message.starts_with("Invalid failed pattern: "), "{}", message);
+
+        assert_eq!(
+            error(regex_spec(None, None, Some("^SKIP (\\S+)"))),
+            (
+                "/log_parser/skipped".to_string(),
+                "The skipped pattern needs a (?P<name>...) group for the test name".to_string()
+            )
+        );
+        // A group called something else doesn't count either
+        assert_eq!(error(regex_spec(Some("^ok (?P<test>\\S+)"), None, None)).0, "/log_parser/passed");
+
+        assert_eq!(error(regex_spec(None, None, None)).0, "/log_parser");
+
+        let mut spec = regex_spec(Some("^ok (?P<name>\\S+)"), None, None);
+        spec.name_normalizer = Some(NameNormalizer { pattern: "(".to_string(), replacement: String::new() });
+        let (pointer, message) = error(spec);
+        assert_eq!(pointer, "/log_parser/name_normalizer/pattern");
+        assert!(message.starts_with("Invalid name_normalizer pattern: "), "{}", message);
+    }
+
+    #[test]
+    fn regex_parser_statuses() {
+        // `passed` is tried before `failed`, so a line both match is a pass
+        let spec = regex_spec(
+            Some("^(?P<name>\\w+) (ok|done)$"),
+            Some("^(?P<name>\\w+) (ok|broke)$"),
+            Some("^SKIP (?P<name>\\w+)"),
+        );
+        assert_regex_results(
+            &spec,
+            "first ok\nsecond broke\nSKIP third\nunrelated line here\nfourth done\n",
+            &[("first", Passed, None), ("second", Failed, None), ("third", Skipped, None), ("fourth", Passed, None)],
+        );
+    }
+
+    #[test]
+    fn regex_parser_keeps_the_last_status() {
+        let spec = regex_spec(Some("^ok (?P<name>\\S+)"), Some("^FAIL (?P<name>\\S+)"), None);
+        assert_regex_results(
+            &spec,
+            "FAIL flaky\nok stable\nok flaky\nok broken\nFAIL broken\n",
+            &[("flaky", Passed, None), ("stable", Passed, None), ("broken", Failed, None)],
+        );
+    }
+
+    #[test]
+    fn regex_parser_durations() {
+        let spec = regex_spec(Some("^ok (?P<name>\\S+)(?: \\((?P<duration>[^)]+)\\))?$"), None, None);
+        assert_regex_results(
+            &spec,
+            "ok plain (12)\nok millis (5ms)\nok spaced (7 ms)\nok seconds (1.5s)\nok none\nok garbage (soon)\n",
+            &[
+                ("plain", Passed, Some(12.0)),
+                ("millis", Passed, Some(5.0)),
+                ("spaced", Passed, Some(7.0)),
+                ("seconds", Passed, Some(1500.0)),
+                ("none", Passed, None),
+                ("garbage", Passed, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn regex_parser_normalizes_names() {
+        let mut spec = regex_spec(Some("^ok (?P<name>\\S+)"), None, None);
+        spec.name_normalizer =
+            Some(NameNormalizer { pattern: "^tests/(\\w+)\\.py$".to_string(), replacement: "$1".to_string() });
+        assert_regex_results(
+            &spec,
+            "ok tests/test_api.py\nok other/test_db.py\n",
+            &[("test_api", Passed, None), ("other/test_db.py", Passed, None)],
+        );
+
+        spec.name_normalizer = Some(NameNormalizer {
+            pattern: "^(?P<file>[^:]+)::(?P<test>\\w+)$".to_string(),
+            replacement: "${test} (${file})".to_string(),
+        });
+        assert_regex_results(&spec, "ok a.py::test_one\n", &[("test_one (a.py)", Passed, None)]);
+
+        // A name the normalizer empties is dropped
+        spec.name_normalizer = Some(NameNormalizer { pattern: "^skip_me$".to_string(), replacement: String::new() });
+        assert_regex_results(&spec, "ok skip_me\nok keep_me\n", &[("keep_me", Passed, None)]);
+    }
+
+    #[tokio::test]
+    async fn regex_parser_strips_colors() {
+        let spec = regex_spec(Some("^ok (?P<name>\\S+)$"), Some("^FAIL (?P<name>\\S+)$"), None);
+        let parser = LogParser::new(REGEX_PARSER, Some(&spec), None).unwrap();
+        let log = "\u{1b}[32mok\u{1b}[0m colored\n\u{1b}[1;31mFAIL\u{1b}[0m \u{1b}[1mbold\u{1b}[0m\n";
+        let results: Vec<(String, TestStatus)> =
+            parser.parse(log).await.unwrap().into_iter().map(|result| (result.name, result.status)).collect();
+        assert_eq!(results, vec![("colored".to_string(), Passed), ("bold".to_string(), Failed)]);
+    }
+
+    // Write an executable sh script into `dir`
+    fn parser_script(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("parser.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    async fn run_script(body: &str, log: &str, timeout: Duration) -> Result<Vec<TestResult>, String> {
+        let dir = tempfile::tempdir().unwrap();
+        run_parser_script(&parser_script(dir.path(), body), log, timeout).await
+    }
+
+    #[tokio::test]
+    async fn parser_script_reads_the_log_on_stdin() {
+        // Echoing the log back makes it the script's result
+        let log = r#"[
+            {"name": " tests/test_api.py::test_get ", "status": "passed", "duration": 12.5},
+            {"name": "tests/test_api.py::test_post", "status": "failed"},
+            {"name": "", "status": "error"},
+            {"name": "tests/test_db.py::test_skip", "status": "skipped", "duration": null}
+        ]"#;
+        let results: Vec<(String, TestStatus, Option<f64>)> = run_script("cat", log, SCRIPT_TIMEOUT)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| (result.name, result.status, result.duration))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("tests/test_api.py::test_get".to_string(), Passed, Some(12.5)),
+                ("tests/test_api.py::test_post".to_string(), Failed, None),
+                ("tests/test_db.py::test_skip".to_string(), Skipped, None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn parser_script_failures() {
+        assert_eq!(
+            run_script("cat > /dev/null\necho 'no such runner' >&2\nexit 3", "log", SCRIPT_TIMEOUT).await.unwrap_err(),
+            "The parser script exited with code 3: no such runner"
+        );
+
+        let error = run_script("echo 'tests passed'", "log", SCRIPT_TIMEOUT).await.unwrap_err();
+        assert!(error.starts_with("The parser script's output is not a JSON list of test results: "), "{}", error);
+
+        let error = run_script(r#"echo '[{"name": "a", "status": "broken"}]'"#, "log", SCRIPT_TIMEOUT).await.unwrap_err();
+        assert!(error.starts_with("The parser script's output is not a JSON list of test results: "), "{}", error);
+        assert!(error.contains("unknown variant `broken`"), "{}", error);
+
+        let dir = tempfile::tempdir().unwrap();
+        let error = run_parser_script(&dir.path().join("missing.sh"), "log", SCRIPT_TIMEOUT).await.unwrap_err();
+        assert!(error.starts_with("Failed to start the parser script "), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn parser_script_times_out() {
+        let started = std::time::Instant::now();
+        let error = run_script("exec sleep 30", "log", Duration::from_secs(1)).await.unwrap_err();
+        assert_eq!(error, "The parser script didn't finish within 1 seconds and was stopped");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::process::Command;
+
+use crate::commands::{check_commit, get_app_dir};
+
+lazy_static::lazy_static! {
+    // One lock per mirror, so two builds of the same repository don't fetch into it at once
+    static ref MIRROR_LOCKS: Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+// ~/.swebench-debugger/mirrors/<host>_<owner>_<repo>.git
+pub fn get_mirror_path(repo_url: &str) -> PathBuf {
+    get_app_dir().join("mirrors").join(mirror_name(repo_url))
+}
+
+// The URL without its scheme and .git suffix, with `/` turned into `_`. `_` and every
+// other character outside [A-Za-z0-9.-] are percent-encoded, and so is a leading `.`,
+// so no two repositories share a mirror.
+fn mirror_name(repo_url: &str) -> String {
+    let trimmed = repo_url.trim().trim_end_matches('/');
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+    let without_scheme = trimmed.split_once("://").map(|(_, rest)| rest).unwrap_or(trimmed);
+    let mut name = String::new();
+    for (index, c) in without_scheme.char_indices() {
+        match c {
+            '/' => name.push('_'),
+            '.' if index == 0 => name.push_str("%2E"),
+            c if c.is_ascii_alphanumeric() || c == '-' || c == '.' => name.push(c),
+            c => {
+                let mut bytes = [0; 4];
+                for byte in c.encode_utf8(&mut bytes).bytes() {
+                    name.push_str(&format!("%{:02X}", byte));
+                }
+            }
+        }
+    }
+    format!("{}.git", name)
+}
+
+// Create the bare mirror of `repo_url`, or bring an existing one up to date, and make
+// sure it has `commit`. Returns the mirror's path. Progress goes to `log`.
+pub async fn update_mirror(repo_url: &str, commit: &str, log: impl Fn(String)) -> Result<PathBuf, String> {
+    let mirror_path = get_mirror_path(repo_url);
+    update_mirror_at(&mirror_path, repo_url, commit, log).await?;
+    Ok(mirror_path)
+}
+
+async fn update_mirror_at(mirror_path: &Path, repo_url: &str, commit: &str, log: impl Fn(String)) -> Result<(), String> {
+    check_commit(commit)?;
+    let lock = MIRROR_LOCKS
+        .lock()
+        .unwrap()
+        .entry(mirror_path.to_path_buf())
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+
+    if mirror_path.join("HEAD").exists() {
+        log(format!("Updating mirror {}", mirror_path.display()));
+        git(mirror_path, &["remote", "update", "--prune"]).await?;
+    } else {
+        log(format!("Creating mirror {}", mirror_path.display()));
+        let parent = mirror_path.parent().ok_or("Invalid mirror path")?;
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create the mirrors directory: {}", e))?;
+        // A half-finished clone from an interrupted build would look like a mirror next time
+        let partial_path = mirror_path.with_extension("partial");
+        let _ = std::fs::remove_dir_all(&partial_path);
+        let partial = partial_path.to_string_lossy().to_string();
+        git(parent, &["clone", "--quiet", "--mirror", "--", repo_url, &partial]).await?;
+        std::fs::rename(&partial_path, mirror_path).map_err(|e| format!("Failed to create the mirror: {}", e))?;
+    }
+
+    // Commits only reachable from refs the mirror doesn't track, like closed PRs, are
+    // fetched by hash
+    let commit_ref = format!("{}^{{commit}}", commit);
+    if git(mirror_path, &["cat-file", "-e", &commit_ref]).await.is_err() {
+        log(format!("Fetching {} into the mirror", commit));
+        git(mirror_path, &["fetch", "--quiet", "origin", commit])
+            .await
+            .map_err(|e| format!("Commit {} not found in {}: {}", commit, repo_url, e))?;
+    }
+
+    Ok(())
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::run_git;
+    use std::fs;
+
+    #[test]
+    fn mirror_names() {
+        assert_eq!(mirror_name("https://github.com/owner/repo"), "github.com_owner_repo.git");
+        assert_eq!(mirror_name(" https://github.com/owner/repo.git/ "), "github.com_owner_repo.git");
+        assert_eq!(mirror_name("http://github.com/owner/repo"), "github.com_owner_repo.git");
+        assert_eq!(mirror_name("https://gitlab.example.com:8443/group/sub/my.repo"), "gitlab.example.com%3A8443_group_sub_my.repo.git");
+        assert_eq!(mirror_name("git@github.com:owner/repo.git"), "git%40github.com%3Aowner_repo.git");
+        assert_eq!(mirror_name("file:///srv/git/repo"), "_srv_git_repo.git");
+        assert_eq!(mirror_name("../repo"), "%2E._repo.git");
+        assert_eq!(mirror_name("https://github.com/owner/répo"), "github.com_owner_r%C3%A9po.git");
+    }
+
+    #[test]
+    fn underscores_dont_collide_with_slashes() {
+        let first = mirror_name("https://github.com/a_b/c");
+        let second = mirror_name("https://github.com/a/b_c");
+        assert_eq!(first, "github.com_a%5Fb_c.git");
+        assert_eq!(second, "github.com_a_b%5Fc.git");
+        assert_ne!(first, second);
+        assert_ne!(mirror_name("https://github.com/a/b%5Fc"), second);
+    }
+
+    fn commit(repo: &Path, file: &str, content: &str) -> String {
+        fs::write(repo.join(file), content).unwrap();
+        run_git(repo, &["add", file]);
+        run_git(repo, &["commit", "-q", "-m", content]);
+        run_git(repo, &["rev-parse", "HEAD"])
+    }
+
+    // A repository to mirror and where its mirror goes, both in a fresh tempdir
+    fn setup() -> (tempfile::TempDir, PathBuf, String, PathBuf) {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("source");
+        fs::create_dir(&source).unwrap();
+        run_git(&source, &["init", "-q"]);
+        let url = format!("file://{}", source.display());
+        let mirror = temp.path().join("mirrors").join("source.git");
+        (temp, source, url, mirror)
+    }
+
+    async fn update(mirror: &Path, url: &str, commit: &str) -> Result<Vec<String>, String> {
+        let log = Mutex::new(Vec::new());
+        update_mirror_at(mirror, url, commit, |message| log.lock().unwrap().push(message)).await?;
+        Ok(log.into_inner().unwrap())
+    }
+
+    fn has_commit(mirror: &Path, commit: &str) -> bool {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(mirror)
+            .args(["cat-file", "-e", &format!("{}^{{commit}}", commit)])
+            .status()
+            .unwrap()
+            .success()
+    }
+
+    #[tokio::test]
+    async fn clones_then_updates_the_mirror() {
+        let (_temp, source, url, mirror) = setup();
+        let first = commit(&source, "a.txt", "first");
+
+        let log = update(&mirror, &url, &first).await.unwrap();
+        assert_eq!(log, vec![format!("Creating mirror {}", mirror.display())]);
+        assert!(mirror.join("HEAD").exists());
+        assert!(has_commit(&mirror, &first));
+
+        // The new commit arrives with the remote update, without a fetch by hash
+        let second = commit(&source, "a.txt", "second");
+        let log = update(&mirror, &url, &second).await.unwrap();
+        assert_eq!(log, vec![format!("Updating mirror {}", mirror.display())]);
+        assert!(has_commit(&mirror, &second));
+    }
+
+    #[tokio::test]
+    async fn fetches_commits_no_ref_reaches() {
+        let (_temp, source, url, mirror) = setup();
+        let first = commit(&source, "a.txt", "first");
+        update(&mirror, &url, &first).await.unwrap();
+
+        // Like the head of a closed PR, the commit exists upstream but no branch has it
+        run_git(&source, &["checkout", "-q", "-b", "closed"]);
+        let orphaned = commit(&source, "b.txt", "closed");
+        run_git(&source, &["checkout", "-q", "main"]);
+        run_git(&source, &["branch", "-q", "-D", "closed"]);
+        run_git(&source, &["config", "uploadpack.allowAnySHA1InWant", "true"]);
+
+        let log = update(&mirror, &url, &orphaned).await.unwrap();
+        assert_eq!(log.last().unwrap(), &format!("Fetching {} into the mirror", orphaned));
+        assert!(has_commit(&mirror, &orphaned));
+
+        let missing = "0123456789abcdef0123456789abcdef01234567";
+        let error = update(&mirror, &url, missing).await.unwrap_err();
+        assert!(error.starts_with(&format!("Commit {} not found in {}", missing, url)), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn replaces_a_partial_clone() {
+        let (_temp, source, url, mirror) = setup();
+        let first = commit(&source, "a.txt", "first");
+        let partial = mirror.with_extension("partial");
+        fs::create_dir_all(partial.join("objects")).unwrap();
+        fs::write(partial.join("objects").join("junk"), "interrupted").unwrap();
+
+        update(&mirror, &url, &first).await.unwrap();
+        assert!(!partial.exists());
+        assert!(has_commit(&mirror, &first));
+    }
+
+    #[tokio::test]
+    async fn rejects_commits_that_look_like_options() {
+        let (_temp, source, url, mirror) = setup();
+        commit(&source, "a.txt", "first");
+        let error = update(&mirror, &url, "--upload-pack=touch pwned").await.unwrap_err();
+        assert_eq!(error, "Invalid commit --upload-pack=touch pwned, a commit can't start with '-'");
+        assert!(!mirror.exists());
+    }
+}
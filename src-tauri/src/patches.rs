@@ -0,0 +1,276 @@
+// Applying SWE-bench `patch` and `test_patch` diffs inside a test container. The diffs
+// are written to the container's stdin, one base64 line each, so any content survives
+// the trip, and applied in /testbed before the test command runs.
+
+// Printed to stderr by the apply script when a patch doesn't apply
+const FAILURE_MARKER: &str = "SWEBENCH_PATCH_FAILED";
+
+// Bash that reads one stdin line per patch, in order, and applies it with git apply.
+// The test command is expected to follow it in the same script.
+pub fn apply_script(names: &[&str]) -> String {
+    apply_script_in("/testbed", names)
+}
+
+fn apply_script_in(dir: &str, names: &[&str]) -> String {
+    let mut script = format!("cd {}\n", dir);
+    for name in names {
+        script.push_str(&format!(
+            "IFS= read -r encoded && printf '%s' \"$encoded\" | base64 -d > /tmp/{0}.diff || {{ echo \"{1} {0} could not be read\" >&2; exit 1; }}\n\
+             git apply -v /tmp/{0}.diff || {{ echo \"{1} {0}\" >&2; exit 1; }}\n",
+            name, FAILURE_MARKER
+        ));
+    }
+    script
+}
+
+// What to write to the container's stdin for apply_script
+pub fn encode_patches(diffs: &[&str]) -> String {
+    diffs.iter().map(|diff| format!("{}\n", base64_encode(diff.as_bytes()))).collect()
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[derive(Debug, Clone)]
+pub struct PatchFailure {
+    pub patch: String,
+    // file:line of the hunk git couldn't apply, when it said which one
+    pub hunk: Option<String>,
+    pub message: Option<String>,
+}
+
+impl PatchFailure {
+    pub fn describe(&self) -> String {
+        match (&self.hunk, &self.message) {
+            (Some(hunk), _) => format!("Failed to apply {}: the hunk at {} does not apply", self.patch, hunk),
+            (None, Some(message)) => format!("Failed to apply {}: {}", self.patch, message),
+            (None, None) => format!("Failed to apply {}", self.patch),
+        }
+    }
+}
+
+// Watches the stderr of a run for git apply errors and the failure marker
+#[derive(Default)]
+pub struct PatchFailureDetector {
+    hunk: Option<String>,
+    message: Option<String>,
+    failure: Option<PatchFailure>,
+}
+
+impl PatchFailureDetector {
+    // Returns false for the marker line, which isn't worth showing in the log
+    pub fn feed(&mut self, line: &str) -> bool {
+        if let Some(rest) = line.strip_prefix(FAILURE_MARKER) {
+            let rest = rest.trim();
+            let (patch, reason) = rest.split_once(' ').unwrap_or((rest, ""));
+            self.failure = Some(PatchFailure {
+                patch: patch.to_string(),
+                hunk: self.hunk.take(),
+                message: if reason.is_empty() { self.message.take() } else { Some(reason.to_string()) },
+            });
+            return false;
+        }
+        if let Some(error) = line.strip_prefix("error: ") {
+            match error.strip_prefix("patch failed: ") {
+                Some(hunk) => self.hunk = Some(hunk.trim().to_string()),
+                None => self.message = Some(error.trim().to_string()),
+            }
+        }
+        true
+    }
+
+    pub fn failure(self) -> Option<PatchFailure> {
+        self.failure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::run_git;
+    use std::io::Write;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    // Decode with the base64 the test containers use
+    fn base64_decode(encoded: &str) -> Vec<u8> {
+        let mut child = Command::new("base64")
+            .arg("-d")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(encoded.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "base64 -d {}", encoded);
+        output.stdout
+    }
+
+    #[test]
+    fn encodes_base64() {
+        // The RFC 4648 test vectors, covering lengths of 0, 1 and 2 mod 3
+        for (input, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64_encode(input.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn base64_round_trips() {
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        let inputs: Vec<Vec<u8>> = vec![
+            Vec::new(),
+            vec![0xff],
+            vec![0xfb, 0xff],
+            vec![0x00, 0x3e, 0x3f],
+            "naïve — 日本語 🦀\r\n".as_bytes().to_vec(),
+            all_bytes.clone(),
+            all_bytes[1..].to_vec(),
+            all_bytes[2..].to_vec(),
+        ];
+        for input in inputs {
+            let encoded = base64_encode(&input);
+            assert_eq!(encoded.len() % 4, 0);
+            assert_eq!(base64_decode(&encoded), input, "{}", encoded);
+        }
+    }
+
+    // A repository with a.txt and b.txt committed
+    fn repository() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        run_git(dir.path(), &["init", "-q"]);
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "alpha\nbeta\ngamma\n").unwrap();
+        run_git(dir.path(), &["add", "a.txt", "b.txt"]);
+        run_git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        dir
+    }
+
+    // Run apply_script on `repo` with the patches on stdin, like a test container
+    // would, feeding its stderr to a PatchFailureDetector
+    fn apply(repo: &Path, patches: &[(&str, &str)]) -> (bool, Vec<String>, Option<PatchFailure>) {
+        let names: Vec<&str> = patches.iter().map(|(name, _)| *name).collect();
+        let diffs: Vec<&str> = patches.iter().map(|(_, diff)| *diff).collect();
+        let script = format!("{}echo tests ran\n", apply_script_in(&repo.to_string_lossy(), &names));
+        let mut child = Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(encode_patches(&diffs).as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        let mut detector = PatchFailureDetector::default();
+        let shown: Vec<String> = String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .filter(|line| detector.feed(line))
+            .map(|line| line.to_string())
+            .collect();
+        let ran = String::from_utf8_lossy(&output.stdout).contains("tests ran");
+        (ran, shown, detector.failure())
+    }
+
+    const CLEAN_PATCH: &str = "diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+";
+
+    #[test]
+    fn applies_patches_before_the_tests() {
+        let repo = repository();
+        // Non-ASCII, `$` and quotes make it through stdin as they are
+        let test_patch = "diff --git a/b.txt b/b.txt
+--- a/b.txt
++++ b/b.txt
+@@ -1,3 +1,4 @@
+ alpha
+ beta
++\"$HOME\" `naïve` 🦀
+ gamma
+";
+        let (ran, _, failure) = apply(repo.path(), &[("patch", CLEAN_PATCH), ("test_patch", test_patch)]);
+        assert!(ran);
+        assert!(failure.is_none(), "{:?}", failure);
+        assert_eq!(std::fs::read_to_string(repo.path().join("a.txt")).unwrap(), "one\nTWO\nthree\n");
+        assert_eq!(std::fs::read_to_string(repo.path().join("b.txt")).unwrap(), "alpha\nbeta\n\"$HOME\" `naïve` 🦀\ngamma\n");
+    }
+
+    #[test]
+    fn reports_the_hunk_that_does_not_apply() {
+        let repo = repository();
+        let conflicting = "diff --git a/b.txt b/b.txt
+--- a/b.txt
++++ b/b.txt
+@@ -2,2 +2,2 @@
+-BETA
++delta
+ gamma
+";
+        let (ran, shown, failure) = apply(repo.path(), &[("patch", CLEAN_PATCH), ("test_patch", conflicting)]);
+        assert!(!ran);
+        let failure = failure.expect("the test_patch doesn't apply");
+        assert_eq!(failure.patch, "test_patch");
+        assert_eq!(failure.hunk.as_deref(), Some("b.txt:2"));
+        assert_eq!(failure.describe(), "Failed to apply test_patch: the hunk at b.txt:2 does not apply");
+        // git's own errors stay in the log, the marker doesn't
+        assert!(shown.iter().any(|line| line == "error: patch failed: b.txt:2"), "{:?}", shown);
+        assert!(!shown.iter().any(|line| line.contains(FAILURE_MARKER)), "{:?}", shown);
+        // The patch before it did apply
+        assert_eq!(std::fs::read_to_string(repo.path().join("a.txt")).unwrap(), "one\nTWO\nthree\n");
+    }
+
+    #[test]
+    fn reports_patches_git_cant_read() {
+        let repo = repository();
+        let (ran, _, failure) = apply(repo.path(), &[("patch", "this is not a diff\n")]);
+        assert!(!ran);
+        let failure = failure.unwrap();
+        assert_eq!(failure.patch, "patch");
+        assert_eq!(failure.hunk, None);
+        assert_eq!(failure.describe(), "Failed to apply patch: No valid patches in input (allow with \"--allow-empty\")");
+    }
+
+    #[test]
+    fn test_output_is_not_a_patch_failure() {
+        let mut detector = PatchFailureDetector::default();
+        for line in [
+            "Checking patch a.txt...",
+            "Applied patch a.txt cleanly.",
+            "npm ERR! Test failed.  See above for more details.",
+            "error: test failed, to rerun pass `--lib`",
+            "error: patch failed: but this is a test's own output",
+            "FAILED tests/test_api.py::test_get - AssertionError",
+        ] {
+            assert!(detector.feed(line), "{}", line);
+        }
+        assert!(detector.failure().is_none());
+    }
+}
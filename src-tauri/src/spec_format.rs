@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::json_spans::escape_pointer_token;
+
+// The formats a spec can be written in, they all end up as a serde_json::Value
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecFormat {
+    Json,
+    Json5,
+    Yaml,
+    Toml,
+}
+
+// A spec that doesn't parse, with the 1-based position the parser reported
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub format: SpecFormat,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SpecFormat {
+    // "auto" and "" mean autodetect, which is None
+    pub fn from_name(name: &str) -> Result<Option<SpecFormat>, String> {
+        match name.trim().to_lowercase().as_str() {
+            "" | "auto" => Ok(None),
+            "json" => Ok(Some(SpecFormat::Json)),
+            "json5" => Ok(Some(SpecFormat::Json5)),
+            "yaml" | "yml" => Ok(Some(SpecFormat::Yaml)),
+            "toml" => Ok(Some(SpecFormat::Toml)),
+            other => Err(format!("Unknown spec format: {}", other)),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpecFormat::Json => "JSON",
+            SpecFormat::Json5 => "JSON5",
+            SpecFormat::Yaml => "YAML",
+            SpecFormat::Toml => "TOML",
+        }
+    }
+}
+
+// Parse a spec in the given format, or figure the format out. Autodetection tries
+// strict JSON first so plain JSON specs keep their exact error messages.
+pub fn parse_spec(input: &str, format: Option<SpecFormat>) -> Result<(Value, SpecFormat), SyntaxError> {
+    if let Some(format) = format {
+        return parse_as(input, format).map(|value| (value, format));
+    }
+
+    let json_error = match parse_as(input, SpecFormat::Json) {
+        Ok(value) => return Ok((value, SpecFormat::Json)),
+        Err(e) => e,
+    };
+
+    let trimmed = input.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with("//") || trimmed.starts_with("/*") {
+        // Looks like JSON, if it's not JSON5 either the strict JSON error is the useful one
+        return parse_as(input, SpecFormat::Json5)
+            .map(|value| (value, SpecFormat::Json5))
+            .map_err(|_| json_error);
+    }
+
+    if let Ok(value) = parse_as(input, SpecFormat::Toml) {
+        return Ok((value, SpecFormat::Toml));
+    }
+    parse_as(input, SpecFormat::Yaml).map(|value| (value, SpecFormat::Yaml))
+}
+
+fn parse_as(input: &str, format: SpecFormat) -> Result<Value, SyntaxError> {
+    let syntax_error = |message: String, line: usize, column: usize| SyntaxError {
+        format,
+        message,
+        line: line.max(1),
+        column: column.max(1),
+    };
+
+    let value = match format {
+        SpecFormat::Json => serde_json::from_str::<Value>(input)
+            .map_err(|e| syntax_error(e.to_string(), e.line(), e.column()))?,
+        SpecFormat::Json5 => json5::from_str::<Value>(input).map_err(|e| {
+            let (line, column) = match &e {
+                json5::Error::Message { location: Some(location), .. } => (location.line, location.column),
+                _ => (1, 1),
+            };
+            syntax_error(e.to_string(), line, column)
+        })?,
+        SpecFormat::Yaml => serde_yaml::from_str::<Value>(input).map_err(|e| {
+            let (line, column) = e.location().map(|l| (l.line(), l.column())).unwrap_or((1, 1));
+            syntax_error(e.to_string(), line, column)
+        })?,
+        SpecFormat::Toml => toml::from_str::<Value>(input).map_err(|e| {
+            let (line, column) = e.span().map(|span| line_column(input, span.start)).unwrap_or((1, 1));
+            syntax_error(e.message().to_string(), line, column)
+        })?,
+    };
+
+    // YAML in particular happily parses plain text as a string
+    if !value.is_object() {
+        return Err(syntax_error(format!("Expected the {} spec to be an object", format.label()), 1, 1));
+    }
+    Ok(value)
+}
+
+pub fn serialize_spec(value: &Value, format: SpecFormat) -> Result<String, String> {
+    match format {
+        // Pretty JSON is valid JSON5 as well
+        SpecFormat::Json | SpecFormat::Json5 => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        SpecFormat::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        SpecFormat::Toml => toml::to_string_pretty(&without_nulls(value, "")?).map_err(|e| e.to_string()),
+    }
+}
+
+// TOML has no null. A null member means the same as a missing one, so it's dropped,
+// a null in a list has no TOML equivalent.
+fn without_nulls(value: &Value, pointer: &str) -> Result<Value, String> {
+    match value {
+        Value::Object(members) => {
+            let mut kept = serde_json::Map::new();
+            for (key, member) in members {
+                if !member.is_null() {
+                    let member_pointer = format!("{}/{}", pointer, escape_pointer_token(key));
+                    kept.insert(key.clone(), without_nulls(member, &member_pointer)?);
+                }
+            }
+            Ok(Value::Object(kept))
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| match item {
+                Value::Null => Err(format!("TOML has no null, remove the null at {}/{}", pointer, index)),
+                item => without_nulls(item, &format!("{}/{}", pointer, index)),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        value => Ok(value.clone()),
+    }
+}
+
+// Columns in UTF-16 code units, like json_spans and the Monaco editor
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset.min(input.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map(|l| l.encode_utf16().count()).unwrap_or(0) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn detect(input: &str) -> Result<SpecFormat, (SpecFormat, String)> {
+        parse_spec(input, None).map(|(_, format)| format).map_err(|e| (e.format, e.message))
+    }
+
+    #[test]
+    fn autodetects_the_format() {
+        assert_eq!(detect(r#"{"test_cmd": "npm test"}"#), Ok(SpecFormat::Json));
+        assert_eq!(detect("{ test_cmd: 'npm test', }"), Ok(SpecFormat::Json5));
+        assert_eq!(detect("// the spec\n{\"test_cmd\": \"npm test\"}"), Ok(SpecFormat::Json5));
+        assert_eq!(detect("/* the spec */ {\"test_cmd\": \"npm test\"}"), Ok(SpecFormat::Json5));
+        assert_eq!(detect("test_cmd = \"npm test\"\n[docker_specs]\nnode_version = \"20\"\n"), Ok(SpecFormat::Toml));
+        assert_eq!(detect("test_cmd: npm test\ndocker_specs:\n  node_version: \"20\"\n"), Ok(SpecFormat::Yaml));
+        // A flow mapping is YAML too, but only tried as JSON5 since it starts with {
+        assert_eq!(detect("{ test_cmd: npm test }").unwrap_err().0, SpecFormat::Json);
+    }
+
+    #[test]
+    fn broken_json_keeps_the_strict_json_error() {
+        let input = "{\n  \"test_cmd\": \"npm test\",\n  \"log_parser_name\" \"jest\"\n}";
+        let error = parse_spec(input, None).err().unwrap();
+        assert_eq!(error.format, SpecFormat::Json);
+        assert_eq!((error.line, error.column), (3, 21));
+        assert!(error.message.starts_with("expected `:`"), "{}", error.message);
+    }
+
+    #[test]
+    fn text_that_isnt_an_object_is_rejected() {
+        assert_eq!(detect("npm test"), Err((SpecFormat::Yaml, "Expected the YAML spec to be an object".to_string())));
+        assert_eq!(detect("- npm test").unwrap_err().1, "Expected the YAML spec to be an object");
+        let error = parse_spec("[1, 2]", Some(SpecFormat::Json)).err().unwrap();
+        assert_eq!(error.message, "Expected the JSON spec to be an object");
+    }
+
+    #[test]
+    fn an_explicit_format_is_not_second_guessed() {
+        let error = parse_spec(r#"{"test_cmd": "npm test"}"#, Some(SpecFormat::Toml)).err().unwrap();
+        assert_eq!(error.format, SpecFormat::Toml);
+        assert_eq!(parse_spec(r#"{"test_cmd": "npm test"}"#, Some(SpecFormat::Yaml)).unwrap().1, SpecFormat::Yaml);
+    }
+
+    #[test]
+    fn toml_drops_null_members() {
+        let spec = json!({"test_cmd": "npm test", "install": null, "docker_specs": {"node_version": "20", "base_image": null}});
+        let toml = serialize_spec(&spec, SpecFormat::Toml).unwrap();
+        assert_eq!(
+            parse_spec(&toml, Some(SpecFormat::Toml)).unwrap().0,
+            json!({"test_cmd": "npm test", "docker_specs": {"node_version": "20"}})
+        );
+
+        let spec = json!({"test_cmd": "npm test", "install": ["npm ci", null]});
+        assert_eq!(serialize_spec(&spec, SpecFormat::Toml).unwrap_err(), "TOML has no null, remove the null at /install/1");
+    }
+}
@@ -0,0 +1,133 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// Specs without a `spec_version` are version 1, the shape from before versioning
+pub const CURRENT_SPEC_VERSION: u64 = 2;
+
+#[derive(Serialize)]
+pub struct MigrationResult {
+    pub spec: String,
+    pub from_version: u64,
+    pub to_version: u64,
+    pub changes: Vec<String>,
+}
+
+type Migration = fn(&mut Map<String, Value>, &mut Vec<String>);
+
+// MIGRATIONS[n] upgrades a version n + 1 spec to version n + 2. When the spec shape
+// changes, bump CURRENT_SPEC_VERSION and append the migration here.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+// Upgrade a parsed spec in place to CURRENT_SPEC_VERSION, returning the version it
+// started at and a description of every change made
+pub fn migrate_spec_value(spec: &mut Value) -> Result<(u64, Vec<String>), String> {
+    let spec = spec.as_object_mut().ok_or("The spec must be an object")?;
+    let from_version = match spec.get("spec_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| format!("spec_version must be a positive integer, got {}", version))?,
+    };
+    if from_version > CURRENT_SPEC_VERSION {
+        return Err(format!(
+            "spec_version {} is newer than the newest version this app supports ({})",
+            from_version, CURRENT_SPEC_VERSION
+        ));
+    }
+
+    let mut changes = Vec::new();
+    for migration in &MIGRATIONS[(from_version - 1) as usize..] {
+        migration(spec, &mut changes);
+    }
+    if from_version < CURRENT_SPEC_VERSION {
+        spec.insert("spec_version".to_string(), Value::from(CURRENT_SPEC_VERSION));
+        changes.push(format!("Set spec_version to {}", CURRENT_SPEC_VERSION));
+    }
+    Ok((from_version, changes))
+}
+
+// Version 1 specs were hand written and loosely checked, so command lists written as
+// a single string and numeric docker_specs versions are common
+fn migrate_v1_to_v2(spec: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    for key in ["pre_install", "install", "build"] {
+        if let Some(Value::String(command)) = spec.get(key) {
+            let commands = Value::Array(vec![Value::String(command.clone())]);
+            spec.insert(key.to_string(), commands);
+            changes.push(format!("Converted {} from a string to a list of commands", key));
+        }
+    }
+
+    if let Some(Value::Object(docker_specs)) = spec.get_mut("docker_specs") {
+        for (key, value) in docker_specs.iter_mut() {
+            if let Value::Number(number) = value {
+                let version = number.to_string();
+                changes.push(format!("Converted docker_specs.{} from the number {} to the string \"{}\"", key, version, version));
+                *value = Value::String(version);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_string_commands_to_lists() {
+        let mut spec = json!({"test_cmd": "npm test", "install": "npm ci", "build": ["npm run build"]});
+        let (from_version, changes) = migrate_spec_value(&mut spec).unwrap();
+        assert_eq!(from_version, 1);
+        assert_eq!(spec["install"], json!(["npm ci"]));
+        assert_eq!(spec["build"], json!(["npm run build"]));
+        assert_eq!(spec["spec_version"], json!(CURRENT_SPEC_VERSION));
+        assert_eq!(
+            changes,
+            vec!["Converted install from a string to a list of commands".to_string(), format!("Set spec_version to {}", CURRENT_SPEC_VERSION)]
+        );
+    }
+
+    #[test]
+    fn converts_numeric_versions_to_strings() {
+        let mut spec = json!({"docker_specs": {"node_version": 18, "python_version": 3.11, "ubuntu_version": "22.04"}});
+        let (_, changes) = migrate_spec_value(&mut spec).unwrap();
+        assert_eq!(spec["docker_specs"], json!({"node_version": "18", "python_version": "3.11", "ubuntu_version": "22.04"}));
+        assert!(changes.contains(&"Converted docker_specs.node_version from the number 18 to the string \"18\"".to_string()));
+        assert!(changes.contains(&"Converted docker_specs.python_version from the number 3.11 to the string \"3.11\"".to_string()));
+    }
+
+    #[test]
+    fn leaves_current_specs_alone() {
+        let mut spec = json!({"spec_version": CURRENT_SPEC_VERSION, "install": ["npm ci"]});
+        let original = spec.clone();
+        let (from_version, changes) = migrate_spec_value(&mut spec).unwrap();
+        assert_eq!(from_version, CURRENT_SPEC_VERSION);
+        assert!(changes.is_empty());
+        assert_eq!(spec, original);
+    }
+
+    #[test]
+    fn rejects_newer_spec_versions() {
+        let mut spec = json!({"spec_version": CURRENT_SPEC_VERSION + 1});
+        assert_eq!(
+            migrate_spec_value(&mut spec).unwrap_err(),
+            format!(
+                "spec_version {} is newer than the newest version this app supports ({})",
+                CURRENT_SPEC_VERSION + 1,
+                CURRENT_SPEC_VERSION
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_non_integer_spec_versions() {
+        for version in [json!(1.5), json!("2"), json!(0), json!(-1)] {
+            let mut spec = json!({"spec_version": version.clone()});
+            assert_eq!(
+                migrate_spec_value(&mut spec).unwrap_err(),
+                format!("spec_version must be a positive integer, got {}", version)
+            );
+        }
+    }
+}
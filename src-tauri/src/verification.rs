@@ -0,0 +1,250 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::log_parsers::{TestResult, TestStatus};
+
+// What happened to a test in one run
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+    // The parser didn't find the test in the log at all
+    Missing,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TestVerdict {
+    pub name: String,
+    // FAIL_TO_PASS or PASS_TO_PASS
+    pub category: &'static str,
+    // With the test_patch alone
+    pub before: Outcome,
+    // With the test_patch and the gold patch
+    pub after: Outcome,
+    pub success: bool,
+}
+
+// A name can show up more than once, say from several test binaries, and any failure wins
+fn outcome(results: &[TestResult], name: &str) -> Outcome {
+    let mut outcome = Outcome::Missing;
+    for result in results.iter().filter(|result| result.name == name) {
+        outcome = match (outcome, result.status) {
+            (_, TestStatus::Failed | TestStatus::Error) | (Outcome::Failed, _) => Outcome::Failed,
+            (_, TestStatus::Passed) | (Outcome::Passed, _) => Outcome::Passed,
+            (_, TestStatus::Skipped) => Outcome::Skipped,
+        };
+    }
+    outcome
+}
+
+// A FAIL_TO_PASS test has to fail without the gold patch and pass with it, a PASS_TO_PASS
+// test has to pass with the gold patch
+pub fn verdicts(
+    fail_to_pass: &[String],
+    pass_to_pass: &[String],
+    before: &[TestResult],
+    after: &[TestResult],
+) -> Vec<TestVerdict> {
+    let verdict = |name: &String, category: &'static str| {
+        let (before, after) = (outcome(before, name), outcome(after, name));
+        let success = match category {
+            "FAIL_TO_PASS" => before != Outcome::Passed && after == Outcome::Passed,
+            _ => after == Outcome::Passed,
+        };
+        TestVerdict { name: name.clone(), category, before, after, success }
+    };
+    fail_to_pass
+        .iter()
+        .map(|name| verdict(name, "FAIL_TO_PASS"))
+        .chain(pass_to_pass.iter().map(|name| verdict(name, "PASS_TO_PASS")))
+        .collect()
+}
+
+// The report.json the SWE-bench harness writes for an instance. The harness only looks
+// at the run with the gold patch, so a FAIL_TO_PASS test that already passed without it
+// is a success here even though its verdict isn't.
+pub fn report(instance_id: &str, verdicts: &[TestVerdict]) -> Value {
+    let status = |category: &str| {
+        let (success, failure): (Vec<&TestVerdict>, Vec<&TestVerdict>) = verdicts
+            .iter()
+            .filter(|verdict| verdict.category == category)
+            .partition(|verdict| verdict.after == Outcome::Passed);
+        let names = |verdicts: Vec<&TestVerdict>| verdicts.iter().map(|verdict| verdict.name.clone()).collect::<Vec<_>>();
+        json!({"success": names(success), "failure": names(failure)})
+    };
+    json!({
+        instance_id: {
+            "patch_is_None": false,
+            "patch_exists": true,
+            "patch_successfully_applied": true,
+            "resolved": verdicts.iter().all(|verdict| verdict.after == Outcome::Passed),
+            "tests_status": {
+                "FAIL_TO_PASS": status("FAIL_TO_PASS"),
+                "PASS_TO_PASS": status("PASS_TO_PASS"),
+                "FAIL_TO_FAIL": {"success": [], "failure": []},
+                "PASS_TO_FAIL": {"success": [], "failure": []},
+            },
+        }
+    })
+}
+
+// The harness reports a gold patch that doesn't apply without any test results
+pub fn patch_failed_report(instance_id: &str) -> Value {
+    json!({
+        instance_id: {
+            "patch_is_None": false,
+            "patch_exists": true,
+            "patch_successfully_applied": false,
+            "resolved": false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(statuses: &[(&str, TestStatus)]) -> Vec<TestResult> {
+        statuses
+            .iter()
+            .map(|(name, status)| TestResult { name: name.to_string(), status: *status, duration: None })
+            .collect()
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn summary(verdicts: &[TestVerdict]) -> Vec<(&str, &str, Outcome, Outcome, bool)> {
+        verdicts
+            .iter()
+            .map(|verdict| (verdict.name.as_str(), verdict.category, verdict.before, verdict.after, verdict.success))
+            .collect()
+    }
+
+    fn verdicts_for_report(fail_to_pass: &[&str], pass_to_pass: &[&str]) -> Vec<TestVerdict> {
+        let after: Vec<(&str, TestStatus)> =
+            fail_to_pass.iter().chain(pass_to_pass).map(|name| (*name, TestStatus::Passed)).collect();
+        verdicts(&names(fail_to_pass), &names(pass_to_pass), &[], &results(&after))
+    }
+
+    #[test]
+    fn judges_each_test_by_its_category() {
+        let before = results(&[
+            ("fixed", TestStatus::Failed),
+            ("still_broken", TestStatus::Failed),
+            ("already_passing", TestStatus::Passed),
+            ("kept", TestStatus::Passed),
+            ("regressed", TestStatus::Passed),
+            ("lost", TestStatus::Passed),
+        ]);
+        let after = results(&[
+            ("fixed", TestStatus::Passed),
+            ("still_broken", TestStatus::Failed),
+            ("already_passing", TestStatus::Passed),
+            ("kept", TestStatus::Passed),
+            ("regressed", TestStatus::Error),
+        ]);
+        let verdicts = verdicts(
+            &names(&["fixed", "still_broken", "already_passing", "new_file"]),
+            &names(&["kept", "regressed", "lost"]),
+            &before,
+            &after,
+        );
+        assert_eq!(
+            summary(&verdicts),
+            vec![
+                ("fixed", "FAIL_TO_PASS", Outcome::Failed, Outcome::Passed, true),
+                ("still_broken", "FAIL_TO_PASS", Outcome::Failed, Outcome::Failed, false),
+                // Passing without the gold patch means the test doesn't show the fix
+                ("already_passing", "FAIL_TO_PASS", Outcome::Passed, Outcome::Passed, false),
+                // A test the log doesn't mention counts as failed
+                ("new_file", "FAIL_TO_PASS", Outcome::Missing, Outcome::Missing, false),
+                ("kept", "PASS_TO_PASS", Outcome::Passed, Outcome::Passed, true),
+                ("regressed", "PASS_TO_PASS", Outcome::Passed, Outcome::Failed, false),
+                ("lost", "PASS_TO_PASS", Outcome::Passed, Outcome::Missing, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_missing_fail_to_pass_test_can_still_pass() {
+        // Tests added by the test_patch often don't exist, or don't collect, before the fix
+        let verdicts = verdicts(&names(&["added"]), &[], &[], &results(&[("added", TestStatus::Passed)]));
+        assert_eq!(summary(&verdicts), vec![("added", "FAIL_TO_PASS", Outcome::Missing, Outcome::Passed, true)]);
+    }
+
+    #[test]
+    fn any_failure_of_a_repeated_test_wins() {
+        let after = results(&[
+            ("t", TestStatus::Passed),
+            ("t", TestStatus::Failed),
+            ("t", TestStatus::Passed),
+            ("s", TestStatus::Skipped),
+        ]);
+        assert_eq!(outcome(&after, "t"), Outcome::Failed);
+        assert_eq!(outcome(&after, "s"), Outcome::Skipped);
+        assert_eq!(outcome(&after, "u"), Outcome::Missing);
+        let verdicts = verdicts(&[], &names(&["s"]), &after, &after);
+        assert!(!verdicts[0].success);
+    }
+
+    #[test]
+    fn report_matches_the_harness() {
+        let before = results(&[
+            ("f2p_fixed", TestStatus::Failed),
+            ("f2p_early", TestStatus::Passed),
+            ("p2p_kept", TestStatus::Passed),
+        ]);
+        let after = results(&[
+            ("f2p_fixed", TestStatus::Passed),
+            ("f2p_broken", TestStatus::Failed),
+            ("f2p_early", TestStatus::Passed),
+            ("p2p_kept", TestStatus::Passed),
+            ("p2p_skipped", TestStatus::Skipped),
+        ]);
+        let verdicts = verdicts(
+            &names(&["f2p_fixed", "f2p_broken", "f2p_early"]),
+            &names(&["p2p_kept", "p2p_regressed", "p2p_skipped"]),
+            &before,
+            &after,
+        );
+        assert_eq!(
+            report("owner__repo-1", &verdicts),
+            json!({
+                "owner__repo-1": {
+                    "patch_is_None": false,
+                    "patch_exists": true,
+                    "patch_successfully_applied": true,
+                    "resolved": false,
+                    "tests_status": {
+                        "FAIL_TO_PASS": {"success": ["f2p_fixed", "f2p_early"], "failure": ["f2p_broken"]},
+                        "PASS_TO_PASS": {"success": ["p2p_kept"], "failure": ["p2p_regressed", "p2p_skipped"]},
+                        "FAIL_TO_FAIL": {"success": [], "failure": []},
+                        "PASS_TO_FAIL": {"success": [], "failure": []}
+                    }
+                }
+            })
+        );
+
+        let passing = verdicts_for_report(&["a"], &["b"]);
+        assert_eq!(report("id", &passing)["id"]["resolved"], json!(true));
+    }
+
+    #[test]
+    fn patch_failed_report_has_no_test_status() {
+        assert_eq!(
+            patch_failed_report("owner__repo-1"),
+            json!({
+                "owner__repo-1": {
+                    "patch_is_None": false,
+                    "patch_exists": true,
+                    "patch_successfully_applied": false,
+                    "resolved": false
+                }
+            })
+        );
+    }
+}
@@ -9,6 +9,17 @@ interface TabsState {
   title: string;
 }
 
+interface LanguageDefinition {
+  name: string;
+  log_parsers: string[];
+}
+
+// Mirrors LanguageRegistry in languages.rs
+interface LanguageRegistry {
+  languages: LanguageDefinition[];
+  load_errors: string[];
+}
+
 // Main App component with tab management
 function App() {
   const [tabs, setTabs] = useState<TabsState[]>([
@@ -24,7 +35,11 @@ function App() {
   const tabBarRef = useRef<HTMLDivElement>(null);
   const [defaultLanguage, setDefaultLanguage] = useState<string>("Javascript");
   const [tabLanguages, setTabLanguages] = useState<{ [tabId: string]: string }>({ "1": "Javascript" });
+  const [languages, setLanguages] = useState<string[]>(["Javascript"]);
+  const [languageLoadErrors, setLanguageLoadErrors] = useState<string[]>([]);
   const [dockerPathLoaded, setDockerPathLoaded] = useState(false);
+  const [logParserScript, setLogParserScript] = useState("");
+  const [logParserScriptLoaded, setLogParserScriptLoaded] = useState(false);
   const [defaultLanguageLoaded, setDefaultLanguageLoaded] = useState(false);
   const [themeLoaded, setThemeLoaded] = useState(false);
   const [scrollPositions, setScrollPositions] = useState<{ [tabId: string]: number }>({});
@@ -42,6 +57,13 @@ function App() {
   // Load all config values on mount
   useEffect(() => {
     const loadAllConfig = async () => {
+      try {
+        const registry = await invoke<LanguageRegistry>("list_languages");
+        setLanguages(registry.languages.map((definition) => definition.name));
+        setLanguageLoadErrors(registry.load_errors);
+      } catch (error) {
+        console.error("Failed to load languages:", error);
+      }
       try {
         const savedDockerPath = await invoke<string>("load_config", { key: "docker_path" });
         setDockerPath(savedDockerPath);
@@ -50,6 +72,14 @@ function App() {
         console.error("Failed to load Docker path:", error);
         setDockerPathLoaded(true);
       }
+      try {
+        const savedScript = await invoke<string>("load_config", { key: "log_parser_script" });
+        setLogParserScript(savedScript);
+        setLogParserScriptLoaded(true);
+      } catch (error) {
+        console.error("Failed to load parser script:", error);
+        setLogParserScriptLoaded(true);
+      }
       try {
         const savedLanguage = await invoke<string>("load_config", { key: "default_language" });
         setDefaultLanguage(savedLanguage || "Javascript");
@@ -107,6 +137,19 @@ function App() {
     saveDockerPath();
   }, [dockerPath, dockerPathLoaded]);
 
+  // Save the agentic parser script when it changes, but only after loaded
+  useEffect(() => {
+    if (!logParserScriptLoaded) return;
+    const saveLogParserScript = async () => {
+      try {
+        await invoke("save_config", { key: "log_parser_script", value: logParserScript.trim() });
+      } catch (error) {
+        console.error("Failed to save parser script:", error);
+      }
+    };
+    saveLogParserScript();
+  }, [logParserScript, logParserScriptLoaded]);
+
   // Save default language when it changes, but only after loaded
   useEffect(() => {
     if (!defaultLanguageLoaded) return;
@@ -386,6 +429,21 @@ function App() {
                     : "Using Docker from system PATH"}
                 </div>
               </div>
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                  Parser Script
+                </label>
+                <input
+                  type="text"
+                  value={logParserScript}
+                  onChange={(e) => setLogParserScript(e.target.value)}
+                  className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500 dark:bg-gray-700 dark:text-white"
+                  placeholder="Executable for the agentic log parser"
+                />
+                <div className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  Specs with log_parser_name "agentic" pipe their test output through it, unless they set log_parser_script
+                </div>
+              </div>
               <div className="mt-4">
                 <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
                   Default Language
@@ -396,9 +454,9 @@ function App() {
                     onChange={e => setDefaultLanguage(e.target.value)}
                     className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white dark:!bg-gray-800 text-gray-700 dark:text-white transition-colors pr-8 appearance-none"
                   >
-                    <option value="Javascript">Javascript</option>
-                    <option value="Rust">Rust</option>
-                    <option value="C/CPP">C/CPP</option>
+                    {languages.map((lang) => (
+                      <option key={lang} value={lang}>{lang}</option>
+                    ))}
                   </select>
                   <span className="pointer-events-none absolute right-3 top-1/2 transform -translate-y-1/2 text-gray-500 dark:text-gray-300 text-base">
                     ▼
@@ -410,6 +468,24 @@ function App() {
         </div>
       )}
 
+      {/* Custom language files that couldn't be loaded */}
+      {languageLoadErrors.length > 0 && (
+        <div className="flex items-start gap-3 px-4 py-2 bg-yellow-50 dark:bg-yellow-900/30 border-b border-yellow-200 dark:border-yellow-800 text-sm text-yellow-800 dark:text-yellow-200">
+          <div className="flex-1">
+            {languageLoadErrors.map((error) => (
+              <div key={error}>{error}</div>
+            ))}
+          </div>
+          <button
+            onClick={() => setLanguageLoadErrors([])}
+            className="text-yellow-600 hover:text-yellow-800 dark:text-yellow-300 dark:hover:text-yellow-100"
+            title="Dismiss"
+          >
+            <svg width="16" height="16" fill="none" viewBox="0 0 24 24" stroke="currentColor"><path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" /></svg>
+          </button>
+        </div>
+      )}
+
       {/* Tab Content */}
       <div className="flex-1 min-h-0 overflow-y-auto">
         {tabs.map((tab) => (
@@ -423,6 +499,7 @@ function App() {
             dockerPath={dockerPath}
             setDockerPath={setDockerPath}
             language={tabLanguages[tab.id] || defaultLanguage}
+            languages={languages}
             setLanguage={(lang) => setTabLanguages((prev) => ({ ...prev, [tab.id]: lang }))}
             scrollPosition={getScrollPosition(tab.id)}
             scrollableRef={getOrCreateScrollableRef(tab.id)}
@@ -0,0 +1,105 @@
+import { ChangeEvent, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { FiChevronDown, FiChevronRight, FiDownload } from "react-icons/fi";
+
+// Mirrors InstanceRecord in instances.rs
+export interface InstanceRecord {
+  instance_id: string | null;
+  repo: string;
+  github_repo_url: string;
+  base_commit: string;
+  patch: string;
+  test_patch: string;
+  FAIL_TO_PASS: string[];
+  PASS_TO_PASS: string[];
+  test_files: string[];
+  language: string;
+  language_reason: string;
+}
+
+interface ImportInstanceProps {
+  onImport: (record: InstanceRecord) => void;
+}
+
+export default function ImportInstance({ onImport }: ImportInstanceProps) {
+  const [isExpanded, setIsExpanded] = useState(false);
+  const [input, setInput] = useState("");
+  const [line, setLine] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [imported, setImported] = useState<InstanceRecord | null>(null);
+
+  const handleImport = async () => {
+    try {
+      const record = await invoke<InstanceRecord>("import_instance", {
+        input,
+        line: line.trim() ? parseInt(line.trim(), 10) : null,
+      });
+      setError(null);
+      setImported(record);
+      onImport(record);
+    } catch (err) {
+      setError(`${err}`);
+    }
+  };
+
+  return (
+    <div className="border border-gray-200 dark:border-gray-700 rounded-md">
+      <button
+        onClick={() => setIsExpanded(!isExpanded)}
+        className="w-full flex items-center gap-2 px-3 py-2 text-sm font-medium text-gray-700 dark:text-gray-300 hover:bg-gray-50 dark:hover:bg-gray-800 transition-colors"
+      >
+        {isExpanded ? <FiChevronDown size={16} /> : <FiChevronRight size={16} />}
+        Import SWE-bench Instance
+        {imported && (
+          <span className="text-xs font-normal text-gray-500 dark:text-gray-400">
+            {imported.instance_id || imported.repo}
+          </span>
+        )}
+      </button>
+      {isExpanded && (
+        <div className="p-3 space-y-2 border-t border-gray-200 dark:border-gray-700">
+          <textarea
+            value={input}
+            onChange={(e: ChangeEvent<HTMLTextAreaElement>) => setInput(e.target.value)}
+            rows={5}
+            spellCheck={false}
+            className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm font-mono text-xs focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500 dark:bg-gray-700 dark:text-white"
+            placeholder="Paste an instance as JSON, or the lines of a JSONL file..."
+          />
+          <div className="flex items-center gap-4">
+            <label className="text-sm text-gray-700 dark:text-gray-300">JSONL line</label>
+            <input
+              type="text"
+              value={line}
+              onChange={(e: ChangeEvent<HTMLInputElement>) => setLine(e.target.value)}
+              className="w-24 px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500 dark:bg-gray-700 dark:text-white"
+              placeholder="First"
+            />
+            <button
+              onClick={handleImport}
+              disabled={!input.trim()}
+              className={`px-4 py-1.5 font-medium rounded-md shadow-sm transition-colors flex items-center gap-2 ${
+                !input.trim()
+                  ? "bg-gray-400 cursor-not-allowed text-gray-700"
+                  : "bg-blue-600 hover:bg-blue-700 text-white"
+              }`}
+            >
+              <FiDownload size={16} />
+              Import
+            </button>
+          </div>
+          {error && (
+            <div className="text-sm text-red-600 dark:text-red-400">{error}</div>
+          )}
+          {imported && !error && (
+            <div className="text-xs text-gray-500 dark:text-gray-400">
+              {imported.language} ({imported.language_reason}),{" "}
+              {imported.FAIL_TO_PASS.length} FAIL_TO_PASS and{" "}
+              {imported.PASS_TO_PASS.length} PASS_TO_PASS tests
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
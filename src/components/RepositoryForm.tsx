@@ -1,10 +1,23 @@
-import { ChangeEvent } from "react";
-import Editor from "@monaco-editor/react";
+import { ChangeEvent, useEffect, useId, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import Editor, { OnMount } from "@monaco-editor/react";
 import { FiChevronDown, FiChevronRight, FiCopy } from "react-icons/fi";
+import type { ValidationIssue } from "./Tab";
+
+type MonacoEditor = Parameters<OnMount>[0];
+type Monaco = Parameters<OnMount>[1];
+
+// Monaco's JSON schema settings are global, so every tab's spec editor registers its
+// language schema here under its own model path
+const specSchemas = new Map<string, { uri: string; fileMatch: string[]; schema: any }>();
 
 interface RepositoryFormProps {
   githubRepoUrl: string;
   setGithubRepoUrl: (value: string) => void;
+  localRepoPath: string;
+  setLocalRepoPath: (value: string) => void;
+  useMirror: boolean;
+  setUseMirror: (value: boolean) => void;
   baseCommit: string;
   setBaseCommit: (value: string) => void;
   headCommit: string;
@@ -15,16 +28,22 @@ interface RepositoryFormProps {
   setIsDockerfileExpanded: (value: boolean) => void;
   generatedDockerfile: string;
   validationError: string | null;
+  validationIssues: ValidationIssue[];
   isValidJson: boolean;
   useHeadCommit: boolean;
   setUseHeadCommit: (value: boolean) => void;
   language: string;
+  languages: string[];
   setLanguage: (lang: string) => void;
 }
 
 export default function RepositoryForm({
   githubRepoUrl,
   setGithubRepoUrl,
+  localRepoPath,
+  setLocalRepoPath,
+  useMirror,
+  setUseMirror,
   baseCommit,
   setBaseCommit,
   headCommit,
@@ -35,12 +54,85 @@ export default function RepositoryForm({
   setIsDockerfileExpanded,
   generatedDockerfile,
   validationError,
+  validationIssues,
   isValidJson,
   useHeadCommit,
   setUseHeadCommit,
   language,
+  languages,
   setLanguage,
 }: RepositoryFormProps) {
+  const specEditorRef = useRef<MonacoEditor | null>(null);
+  const monacoRef = useRef<Monaco | null>(null);
+  const specModelPath = `spec-${useId().replace(/:/g, "")}.json`;
+
+  const handleSpecEditorMount: OnMount = (editor, monaco) => {
+    specEditorRef.current = editor;
+    monacoRef.current = monaco;
+    updateSpecMarkers();
+    updateSpecSchema();
+  };
+
+  // Completion and hover docs for the spec fields of the selected language
+  const updateSpecSchema = async () => {
+    const monaco = monacoRef.current;
+    if (!monaco) return;
+    try {
+      const schema = await invoke<any>("export_spec_schema", { language });
+      specSchemas.set(specModelPath, {
+        uri: `${schema.$id}/${specModelPath}`,
+        fileMatch: [specModelPath],
+        schema,
+      });
+      // Validation errors come from the backend, the schema is only used for completion
+      monaco.languages.json.jsonDefaults.setDiagnosticsOptions({
+        validate: false,
+        schemas: Array.from(specSchemas.values()),
+      });
+    } catch (error) {
+      console.error("Failed to load spec schema:", error);
+    }
+  };
+
+  useEffect(() => {
+    updateSpecSchema();
+  }, [language]);
+
+  useEffect(() => {
+    return () => {
+      specSchemas.delete(specModelPath);
+    };
+  }, []);
+
+  // Underline the validation issues in the JSON spec editor
+  const updateSpecMarkers = () => {
+    const model = specEditorRef.current?.getModel();
+    const monaco = monacoRef.current;
+    if (!model || !monaco) return;
+    monaco.editor.setModelMarkers(
+      model,
+      "spec-validation",
+      validationIssues.map((issue) => ({
+        startLineNumber: issue.line,
+        startColumn: issue.column,
+        endLineNumber: issue.end_line,
+        endColumn: issue.end_column,
+        message: issue.suggestion
+          ? `${issue.message}\n${issue.suggestion}`
+          : issue.message,
+        code: issue.rule ?? undefined,
+        severity:
+          issue.severity === "warning"
+            ? monaco.MarkerSeverity.Warning
+            : monaco.MarkerSeverity.Error,
+      }))
+    );
+  };
+
+  useEffect(() => {
+    updateSpecMarkers();
+  }, [validationIssues]);
+
   const handleCopyDockerfile = async () => {
     try {
       await navigator.clipboard.writeText(generatedDockerfile);
@@ -84,9 +176,9 @@ export default function RepositoryForm({
               className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white dark:bg-gray-800 text-gray-700 dark:text-white transition-colors pr-8 appearance-none"
               style={{ minWidth: 0 }}
             >
-              <option value="Javascript">Javascript</option>
-              <option value="Rust">Rust</option>
-              <option value="C/CPP">C/CPP</option>
+              {languages.map((lang) => (
+                <option key={lang} value={lang}>{lang}</option>
+              ))}
             </select>
             <span className="pointer-events-none absolute right-3 top-1/2 transform -translate-y-1/2 text-gray-500 dark:text-gray-300 text-base">
               ▼
@@ -107,6 +199,33 @@ export default function RepositoryForm({
         </div>
       </div>
 
+      {/* Local checkout, replaces cloning the GitHub repo when set */}
+      <div className="flex items-center gap-4">
+        <label className="text-sm font-medium text-gray-700 dark:text-gray-300 min-w-[100px]">
+          Local Repo
+        </label>
+        <input
+          type="text"
+          value={localRepoPath}
+          onChange={(e: ChangeEvent<HTMLInputElement>) => setLocalRepoPath(e.target.value)}
+          className="flex-1 px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500 bg-white dark:bg-gray-800 text-gray-700 dark:text-white transition-colors"
+          placeholder="Optional path to a local or bare repository, used instead of cloning the URL..."
+        />
+        <label
+          className="flex items-center gap-1 cursor-pointer"
+          title="Keep a mirror of the GitHub repo on this machine and build from it instead of cloning inside the image"
+        >
+          <input
+            type="checkbox"
+            checked={useMirror}
+            disabled={localRepoPath.trim() !== ""}
+            onChange={(e: ChangeEvent<HTMLInputElement>) => setUseMirror(e.target.checked)}
+            className="text-blue-600"
+          />
+          <span className="text-sm text-gray-700 dark:text-gray-300">Mirror cache</span>
+        </label>
+      </div>
+
       {/* Second Row: Base and Head Commit */}
       <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
         <div className="flex items-center gap-4">
@@ -154,7 +273,7 @@ export default function RepositoryForm({
         {/* Validation status indicator */}
         {!isValidJson && (
           <div className="mb-2 p-2 bg-red-100 dark:bg-red-900 border border-red-300 dark:border-red-700 rounded-md">
-            <p className="text-sm text-red-700 dark:text-red-300">Invalid JSON syntax</p>
+            <p className="text-sm text-red-700 dark:text-red-300">Invalid spec syntax</p>
           </div>
         )}
         {validationError && isValidJson && (
@@ -171,9 +290,11 @@ export default function RepositoryForm({
         }`}>
           <Editor
             height="250px"
+            path={specModelPath}
             defaultLanguage="json"
             value={jsonSpec}
             onChange={(value: string | undefined) => setJsonSpec(value || "")}
+            onMount={handleSpecEditorMount}
             theme="vs-dark"
             options={{
               minimap: { enabled: false },
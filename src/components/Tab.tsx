@@ -4,25 +4,83 @@ import { listen } from "@tauri-apps/api/event";
 import RepositoryForm from "./RepositoryForm";
 import BuildSection from "./BuildSection";
 import TestSection from "./TestSection";
+import ImportInstance, { type InstanceRecord } from "./ImportInstance";
 import "../App.css";
 
+export interface ValidationIssue {
+  pointer: string;
+  line: number;
+  column: number;
+  end_line: number;
+  end_column: number;
+  message: string;
+  severity: "error" | "warning";
+  rule: string | null;
+  suggestion: string | null;
+}
+
 interface ValidationResult {
   success: boolean;
   error?: string;
   dockerfile?: string;
+  context_files: Record<string, string>;
+  issues: ValidationIssue[];
+  input_format: "json" | "json5" | "yaml" | "toml" | null;
+}
+
+// Mirrors TestResult and TestSummary in log_parsers.rs
+export interface TestResult {
+  name: string;
+  status: "passed" | "failed" | "skipped" | "error";
+  duration: number | null;
+}
+
+export interface TestSummary {
+  passed: number;
+  failed: number;
+  skipped: number;
+  error: number;
+}
+
+// Mirrors TestVerdict in verification.rs
+export interface TestVerdict {
+  name: string;
+  category: "FAIL_TO_PASS" | "PASS_TO_PASS";
+  before: "passed" | "failed" | "skipped" | "missing";
+  after: "passed" | "failed" | "skipped" | "missing";
+  success: boolean;
 }
 
+// Mirrors RepoSource in commands.rs
+type RepoSource =
+  | { kind: "clone" }
+  | { kind: "local"; path: string }
+  | { kind: "mirror" };
+
 interface TabState {
   title: string;
   githubRepoUrl: string;
+  localRepoPath: string;
+  useMirror: boolean;
   baseCommit: string;
   headCommit: string;
   jsonSpec: string;
   imageName: string;
   testFiles: string;
+  patch: string;
+  testPatch: string;
+  instanceId: string;
+  failToPass: string[];
+  passToPass: string[];
+  testResults: TestResult[];
+  testSummary: TestSummary | null;
+  verdicts: TestVerdict[];
+  verifyReport: any | null;
   isDockerfileExpanded: boolean;
   generatedDockerfile: string;
+  generatedContextFiles: Record<string, string>;
   validationError: string | null;
+  validationIssues: ValidationIssue[];
   isValidJson: boolean;
   useHeadCommit: boolean;
   isBuilding: boolean;
@@ -41,14 +99,19 @@ interface TabState {
 type TabAction =
   | { type: "SET_TITLE"; payload: string }
   | { type: "SET_GITHUB_REPO_URL"; payload: string }
+  | { type: "SET_LOCAL_REPO_PATH"; payload: string }
+  | { type: "SET_USE_MIRROR"; payload: boolean }
   | { type: "SET_BASE_COMMIT"; payload: string }
   | { type: "SET_HEAD_COMMIT"; payload: string }
   | { type: "SET_JSON_SPEC"; payload: string }
   | { type: "SET_IMAGE_NAME"; payload: string }
   | { type: "SET_TEST_FILES"; payload: string }
+  | { type: "SET_PATCH"; payload: string }
+  | { type: "SET_TEST_PATCH"; payload: string }
   | { type: "SET_DOCKERFILE_EXPANDED"; payload: boolean }
   | { type: "SET_GENERATED_DOCKERFILE"; payload: string }
   | { type: "SET_VALIDATION_ERROR"; payload: string | null }
+  | { type: "SET_VALIDATION_ISSUES"; payload: ValidationIssue[] }
   | { type: "SET_IS_VALID_JSON"; payload: boolean }
   | { type: "SET_USE_HEAD_COMMIT"; payload: boolean }
   | { type: "SET_IS_BUILDING"; payload: boolean }
@@ -69,6 +132,8 @@ type TabAction =
 const defaultTabState: TabState = {
   title: "Untitled",
   githubRepoUrl: "",
+  localRepoPath: "",
+  useMirror: false,
   baseCommit: "",
   headCommit: "",
   jsonSpec: `{
@@ -77,9 +142,20 @@ const defaultTabState: TabState = {
 }`,
   imageName: "",
   testFiles: "",
+  patch: "",
+  testPatch: "",
+  instanceId: "",
+  failToPass: [],
+  passToPass: [],
+  testResults: [],
+  testSummary: null,
+  verdicts: [],
+  verifyReport: null,
   isDockerfileExpanded: false,
   generatedDockerfile: "",
+  generatedContextFiles: {},
   validationError: null,
+  validationIssues: [],
   isValidJson: true,
   useHeadCommit: false,
   isBuilding: false,
@@ -101,6 +177,10 @@ function tabReducer(state: TabState, action: TabAction): TabState {
       return { ...state, title: action.payload };
     case "SET_GITHUB_REPO_URL":
       return { ...state, githubRepoUrl: action.payload };
+    case "SET_LOCAL_REPO_PATH":
+      return { ...state, localRepoPath: action.payload };
+    case "SET_USE_MIRROR":
+      return { ...state, useMirror: action.payload };
     case "SET_BASE_COMMIT":
       return { ...state, baseCommit: action.payload };
     case "SET_HEAD_COMMIT":
@@ -111,12 +191,18 @@ function tabReducer(state: TabState, action: TabAction): TabState {
       return { ...state, imageName: action.payload };
     case "SET_TEST_FILES":
       return { ...state, testFiles: action.payload };
+    case "SET_PATCH":
+      return { ...state, patch: action.payload };
+    case "SET_TEST_PATCH":
+      return { ...state, testPatch: action.payload };
     case "SET_DOCKERFILE_EXPANDED":
       return { ...state, isDockerfileExpanded: action.payload };
     case "SET_GENERATED_DOCKERFILE":
       return { ...state, generatedDockerfile: action.payload };
     case "SET_VALIDATION_ERROR":
       return { ...state, validationError: action.payload };
+    case "SET_VALIDATION_ISSUES":
+      return { ...state, validationIssues: action.payload };
     case "SET_IS_VALID_JSON":
       return { ...state, isValidJson: action.payload };
     case "SET_USE_HEAD_COMMIT":
@@ -161,6 +247,7 @@ export function SWEBenchTab({
   dockerPath,
   visible,
   language,
+  languages,
   setLanguage,
   scrollPosition,
   scrollableRef,
@@ -171,6 +258,7 @@ export function SWEBenchTab({
   setDockerPath: (path: string) => void;
   visible: boolean;
   language: string;
+  languages: string[];
   setLanguage: (lang: string) => void;
   scrollPosition: number;
   scrollableRef: (el: HTMLDivElement | null) => void;
@@ -188,14 +276,21 @@ export function SWEBenchTab({
     }
   };
 
-  // Validate JSON syntax
-  const validateJsonSyntax = (jsonString: string): boolean => {
-    try {
-      JSON.parse(jsonString);
-      return true;
-    } catch {
-      return false;
-    }
+  // A local checkout, when given, is used instead of cloning the GitHub URL. The
+  // mirror cache clones the URL once on this machine and reuses it across builds.
+  const repoSource: RepoSource = state.localRepoPath.trim()
+    ? { kind: "local", path: state.localRepoPath.trim() }
+    : state.useMirror
+      ? { kind: "mirror" }
+      : { kind: "clone" };
+
+  // Read the spec as JSON, whichever format it was written in
+  const parseSpec = async (spec: string): Promise<any> => {
+    const json = await invoke<string>("convert_spec", {
+      input: spec,
+      toFormat: "json",
+    });
+    return JSON.parse(json);
   };
 
   // Validate Docker image name
@@ -304,8 +399,38 @@ export function SWEBenchTab({
   // Generate Dockerfile from JSON spec using backend validation
   useEffect(() => {
     const generateDockerfile = async () => {
-      // First, check JSON syntax
-      const syntaxValid = validateJsonSyntax(state.jsonSpec);
+      const commitToUse = state.useHeadCommit
+        ? state.headCommit
+        : state.baseCommit;
+      const missingRepo = !state.githubRepoUrl.trim() && repoSource.kind !== "local";
+      const missingCommit = !commitToUse.trim();
+
+      // One call validates and generates. The backend accepts JSON, JSON5, YAML and
+      // TOML and knows where a syntax error is, so it can be underlined. Without a
+      // repository or commit the Dockerfile is discarded but the issues still show.
+      let result: ValidationResult;
+      try {
+        result = await invoke<ValidationResult>("generate_docker_file", {
+          inputJson: state.jsonSpec,
+          githubRepoUrl: state.githubRepoUrl.trim(),
+          commit: commitToUse.trim(),
+          language: language,
+          source: repoSource,
+        });
+      } catch (error) {
+        logError("Failed to generate Dockerfile:", error);
+        dispatch({
+          type: "UPDATE_MULTIPLE",
+          payload: {
+            validationError:
+              "Failed to communicate with backend: " + String(error),
+            generatedDockerfile:
+              "# Backend error\n# Failed to generate Dockerfile",
+          },
+        });
+        return;
+      }
+      const syntaxValid = result.input_format !== null;
       dispatch({ type: "SET_IS_VALID_JSON", payload: syntaxValid });
 
       if (!syntaxValid) {
@@ -313,16 +438,18 @@ export function SWEBenchTab({
           type: "UPDATE_MULTIPLE",
           payload: {
             validationError:
-              "Invalid JSON syntax. Please fix the JSON before proceeding.",
+              result.error ??
+              "Invalid spec syntax. Please fix the spec before proceeding.",
             generatedDockerfile:
-              "# Invalid JSON syntax\n# Please check your JSON format",
+              "# Invalid spec syntax\n# Please check your spec format",
+            validationIssues: result.issues,
           },
         });
         return;
       }
 
       // Check if we have the required inputs
-      if (!state.githubRepoUrl.trim()) {
+      if (missingRepo) {
         dispatch({
           type: "UPDATE_MULTIPLE",
           payload: {
@@ -330,15 +457,13 @@ export function SWEBenchTab({
               "GitHub repository URL is required for Dockerfile generation.",
             generatedDockerfile:
               "# Missing GitHub repository URL\n# Please enter a GitHub repository URL",
+            validationIssues: result.issues,
           },
         });
         return;
       }
 
-      const commitToUse = state.useHeadCommit
-        ? state.headCommit
-        : state.baseCommit;
-      if (!commitToUse.trim()) {
+      if (missingCommit) {
         dispatch({
           type: "UPDATE_MULTIPLE",
           payload: {
@@ -350,49 +475,30 @@ export function SWEBenchTab({
             } commit\n# Please enter a ${
               state.useHeadCommit ? "head" : "base"
             } commit hash`,
+            validationIssues: result.issues,
           },
         });
         return;
       }
 
-      dispatch({ type: "SET_VALIDATION_ERROR", payload: null });
-
-      try {
-        // Call the backend validation and generation
-        const result = await invoke<ValidationResult>("generate_docker_file", {
-          inputJson: state.jsonSpec,
-          githubRepoUrl: state.githubRepoUrl.trim(),
-          commit: commitToUse.trim(),
-          language: language,
+      if (result.success && result.dockerfile) {
+        dispatch({
+          type: "UPDATE_MULTIPLE",
+          payload: {
+            generatedDockerfile: result.dockerfile,
+            generatedContextFiles: result.context_files,
+            validationError: null,
+            validationIssues: result.issues,
+          },
         });
-
-        if (result.success && result.dockerfile) {
-          dispatch({
-            type: "UPDATE_MULTIPLE",
-            payload: {
-              generatedDockerfile: result.dockerfile,
-              validationError: null,
-            },
-          });
-        } else if (result.error) {
-          dispatch({
-            type: "UPDATE_MULTIPLE",
-            payload: {
-              validationError: result.error,
-              generatedDockerfile:
-                "# Validation failed\n# " + result.error.replace(/\n/g, "\n# "),
-            },
-          });
-        }
-      } catch (error) {
-        logError("Failed to generate Dockerfile:", error);
+      } else if (result.error) {
         dispatch({
           type: "UPDATE_MULTIPLE",
           payload: {
-            validationError:
-              "Failed to communicate with backend: " + String(error),
+            validationError: result.error,
+            validationIssues: result.issues,
             generatedDockerfile:
-              "# Backend error\n# Failed to generate Dockerfile",
+              "# Validation failed\n# " + result.error.replace(/\n/g, "\n# "),
           },
         });
       }
@@ -402,6 +508,8 @@ export function SWEBenchTab({
   }, [
     state.jsonSpec,
     state.githubRepoUrl,
+    state.localRepoPath,
+    state.useMirror,
     state.baseCommit,
     state.headCommit,
     state.useHeadCommit,
@@ -515,9 +623,39 @@ export function SWEBenchTab({
       }
     );
 
+    const unlistenTestResults = listen<any>("test_results", (event) => {
+      if (event.payload && event.payload.tab_id === tabId) {
+        dispatch({
+          type: "UPDATE_MULTIPLE",
+          payload: {
+            testResults: event.payload.results,
+            testSummary: event.payload.summary,
+          },
+        });
+      }
+    });
+
+    const unlistenVerifyComplete = listen<any>(
+      "verify_complete",
+      (event) => {
+        if (event.payload && event.payload.tab_id === tabId) {
+          dispatch({
+            type: "UPDATE_MULTIPLE",
+            payload: {
+              isTesting: false,
+              verdicts: event.payload.verdicts || [],
+              verifyReport: event.payload.report,
+            },
+          });
+        }
+      }
+    );
+
     return () => {
       unlistenTestLog.then((f) => f());
       unlistenTestComplete.then((f) => f());
+      unlistenTestResults.then((f) => f());
+      unlistenVerifyComplete.then((f) => f());
     };
   }, [tabId]);
 
@@ -548,9 +686,11 @@ export function SWEBenchTab({
       await invoke("build_docker_image", {
         tabId,
         dockerfileContent: state.generatedDockerfile,
+        contextFiles: state.generatedContextFiles,
         imageName: state.imageName.trim(),
         githubRepoUrl: state.githubRepoUrl.trim(),
         commit: commitToUse.trim(),
+        source: repoSource,
         dockerPath: dockerPath.trim(),
       });
     } catch (error) {
@@ -572,13 +712,19 @@ export function SWEBenchTab({
   const handleTest = async () => {
     if (!state.isImageExists || state.isTesting) return;
 
-    // Extract test_cmd from the JSON spec
+    // Extract test_cmd and the log parser settings from the spec
     let testCmd = "";
+    let logParserName = "";
+    let logParser = null;
+    let logParserScript: string | null = null;
     try {
-      const parsedSpec = JSON.parse(state.jsonSpec);
+      const parsedSpec = await parseSpec(state.jsonSpec);
       testCmd = parsedSpec.test_cmd || "";
+      logParserName = parsedSpec.log_parser_name || "";
+      logParser = parsedSpec.log_parser ?? null;
+      logParserScript = parsedSpec.log_parser_script || null;
     } catch (error) {
-      logError("Failed to parse JSON spec:", error);
+      logError("Failed to parse spec:", error);
       return;
     }
 
@@ -592,6 +738,8 @@ export function SWEBenchTab({
       payload: {
         isTesting: true,
         shouldAutoScrollTest: true,
+        testResults: [],
+        testSummary: null,
       },
     });
     dispatch({ type: "CLEAR_TEST_LOGS" });
@@ -602,6 +750,11 @@ export function SWEBenchTab({
         imageName: state.imageName.trim(),
         testCmd: testCmd.trim(),
         testFilePaths: state.testFiles.trim(),
+        patch: state.patch.trim() ? state.patch : null,
+        testPatch: state.testPatch.trim() ? state.testPatch : null,
+        logParserName: logParserName || null,
+        logParser,
+        logParserScript,
         dockerPath: dockerPath.trim(),
       });
     } catch (error) {
@@ -611,6 +764,77 @@ export function SWEBenchTab({
     }
   };
 
+  const handleImportInstance = (record: InstanceRecord) => {
+    dispatch({
+      type: "UPDATE_MULTIPLE",
+      payload: {
+        githubRepoUrl: record.github_repo_url,
+        localRepoPath: "",
+        baseCommit: record.base_commit,
+        useHeadCommit: false,
+        patch: record.patch,
+        testPatch: record.test_patch,
+        testFiles: record.test_files.join(" "),
+        instanceId: record.instance_id || "",
+        failToPass: record.FAIL_TO_PASS,
+        passToPass: record.PASS_TO_PASS,
+      },
+    });
+    if (languages.includes(record.language)) {
+      setLanguage(record.language);
+    }
+  };
+
+  const handleVerify = async () => {
+    if (!state.isImageExists || state.isTesting) return;
+
+    let parsedSpec: any;
+    try {
+      parsedSpec = await parseSpec(state.jsonSpec);
+    } catch (error) {
+      logError("Failed to parse spec:", error);
+      return;
+    }
+    const testCmd: string = parsedSpec.test_cmd || "";
+    if (!testCmd.trim()) {
+      logError("No test command found in JSON spec");
+      return;
+    }
+
+    dispatch({
+      type: "UPDATE_MULTIPLE",
+      payload: {
+        isTesting: true,
+        shouldAutoScrollTest: true,
+        verdicts: [],
+        verifyReport: null,
+      },
+    });
+    dispatch({ type: "CLEAR_TEST_LOGS" });
+
+    try {
+      await invoke("verify_instance", {
+        tabId,
+        imageName: state.imageName.trim(),
+        testCmd: testCmd.trim(),
+        testFilePaths: state.testFiles.trim(),
+        logParserName: parsedSpec.log_parser_name || "",
+        logParser: parsedSpec.log_parser ?? null,
+        logParserScript: parsedSpec.log_parser_script || null,
+        instanceId: state.instanceId || null,
+        patch: state.patch,
+        testPatch: state.testPatch,
+        failToPass: state.failToPass,
+        passToPass: state.passToPass,
+        dockerPath: dockerPath.trim(),
+      });
+    } catch (error) {
+      dispatch({ type: "SET_IS_TESTING", payload: false });
+      dispatch({ type: "ADD_TEST_LOG", payload: `ERROR: ${error}` });
+      logError("Verification failed:", error);
+    }
+  };
+
   const handleStopTest = async () => {
     try {
       await invoke("stop_docker_test", { tabId });
@@ -675,11 +899,21 @@ export function SWEBenchTab({
         className="flex-1 w-full h-full overflow-y-auto"
       >
         <div className="max-w-6xl mx-auto space-y-6 pb-16 p-6">
+          <ImportInstance onImport={handleImportInstance} />
+
           <RepositoryForm
             githubRepoUrl={state.githubRepoUrl}
             setGithubRepoUrl={(url) =>
               dispatch({ type: "SET_GITHUB_REPO_URL", payload: url })
             }
+            localRepoPath={state.localRepoPath}
+            setLocalRepoPath={(path) =>
+              dispatch({ type: "SET_LOCAL_REPO_PATH", payload: path })
+            }
+            useMirror={state.useMirror}
+            setUseMirror={(useMirror) =>
+              dispatch({ type: "SET_USE_MIRROR", payload: useMirror })
+            }
             baseCommit={state.baseCommit}
             setBaseCommit={(commit) =>
               dispatch({ type: "SET_BASE_COMMIT", payload: commit })
@@ -698,12 +932,14 @@ export function SWEBenchTab({
             }
             generatedDockerfile={state.generatedDockerfile}
             validationError={state.validationError}
+            validationIssues={state.validationIssues}
             isValidJson={state.isValidJson}
             useHeadCommit={state.useHeadCommit}
             setUseHeadCommit={(useHead) =>
               dispatch({ type: "SET_USE_HEAD_COMMIT", payload: useHead })
             }
             language={language}
+            languages={languages}
             setLanguage={setLanguage}
           />
 
@@ -734,6 +970,14 @@ export function SWEBenchTab({
             setTestFiles={(files) =>
               dispatch({ type: "SET_TEST_FILES", payload: files })
             }
+            patch={state.patch}
+            setPatch={(patch) =>
+              dispatch({ type: "SET_PATCH", payload: patch })
+            }
+            testPatch={state.testPatch}
+            setTestPatch={(patch) =>
+              dispatch({ type: "SET_TEST_PATCH", payload: patch })
+            }
             isTesting={state.isTesting}
             testLogs={state.testLogs}
             shouldAutoScrollTest={state.shouldAutoScrollTest}
@@ -741,6 +985,15 @@ export function SWEBenchTab({
               dispatch({ type: "SET_SHOULD_AUTO_SCROLL_TEST", payload: scroll })
             }
             handleTest={handleTest}
+            handleVerify={handleVerify}
+            canVerify={
+              state.patch.trim() !== "" &&
+              state.failToPass.length + state.passToPass.length > 0
+            }
+            testResults={state.testResults}
+            testSummary={state.testSummary}
+            verdicts={state.verdicts}
+            verifyReport={state.verifyReport}
             handleStopTest={handleStopTest}
             isImageExists={state.isImageExists}
             isCheckingImage={state.isCheckingImage}
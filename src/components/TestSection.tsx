@@ -1,14 +1,25 @@
 import { ChangeEvent, RefObject } from "react";
-import { FiCopy, FiCheck, FiSquare } from "react-icons/fi";
+import { FiCopy, FiCheck, FiSquare, FiCheckCircle } from "react-icons/fi";
+import type { TestResult, TestSummary, TestVerdict } from "./Tab";
 
 interface TestSectionProps {
   testFiles: string;
   setTestFiles: (value: string) => void;
+  patch: string;
+  setPatch: (value: string) => void;
+  testPatch: string;
+  setTestPatch: (value: string) => void;
   isTesting: boolean;
   testLogs: string[];
   shouldAutoScrollTest: boolean;
   setShouldAutoScrollTest: (value: boolean) => void;
   handleTest: () => void;
+  handleVerify: () => void;
+  canVerify: boolean;
+  testResults: TestResult[];
+  testSummary: TestSummary | null;
+  verdicts: TestVerdict[];
+  verifyReport: any | null;
   handleStopTest: () => void;
   isImageExists: boolean;
   isCheckingImage: boolean;
@@ -20,11 +31,21 @@ interface TestSectionProps {
 export default function TestSection({
   testFiles,
   setTestFiles,
+  patch,
+  setPatch,
+  testPatch,
+  setTestPatch,
   isTesting,
   testLogs,
   shouldAutoScrollTest,
   setShouldAutoScrollTest,
   handleTest,
+  handleVerify,
+  canVerify,
+  testResults,
+  testSummary,
+  verdicts,
+  verifyReport,
   handleStopTest,
   isImageExists,
   isCheckingImage,
@@ -47,8 +68,46 @@ export default function TestSection({
     }
   };
 
+  const handleCopyReport = async () => {
+    try {
+      await navigator.clipboard.writeText(JSON.stringify(verifyReport, null, 2));
+    } catch (err) {
+      console.error('Failed to copy report:', err);
+    }
+  };
+
   return (
     <div className="space-y-6">
+      {/* Patches applied with git apply before the tests run */}
+      <div className="grid grid-cols-2 gap-4">
+        <div>
+          <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            Patch
+          </label>
+          <textarea
+            value={patch}
+            onChange={(e: ChangeEvent<HTMLTextAreaElement>) => setPatch(e.target.value)}
+            rows={4}
+            spellCheck={false}
+            className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm font-mono text-xs focus:outline-none focus:ring-2 focus:ring-green-500 focus:border-green-500 dark:bg-gray-700 dark:text-white"
+            placeholder="Optional diff applied first..."
+          />
+        </div>
+        <div>
+          <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+            Test Patch
+          </label>
+          <textarea
+            value={testPatch}
+            onChange={(e: ChangeEvent<HTMLTextAreaElement>) => setTestPatch(e.target.value)}
+            rows={4}
+            spellCheck={false}
+            className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm font-mono text-xs focus:outline-none focus:ring-2 focus:ring-green-500 focus:border-green-500 dark:bg-gray-700 dark:text-white"
+            placeholder="Optional diff applied after the patch..."
+          />
+        </div>
+      </div>
+
       {/* Test Files and Test Button */}
       <div className="flex items-center gap-4">
         <label className="text-sm font-medium text-gray-700 dark:text-gray-300 min-w-[100px]">
@@ -62,23 +121,42 @@ export default function TestSection({
           placeholder="Enter test file paths..."
         />
         {!isTesting ? (
-          <button
-            onClick={handleTest}
-            disabled={!isImageExists || isCheckingImage || isTesting}
-            className={`px-6 py-2 font-medium rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-green-500 focus:ring-offset-2 transition-colors flex items-center gap-2 ${
-              !isImageExists || isCheckingImage || isTesting
-                ? 'bg-gray-400 cursor-not-allowed text-gray-700'
-                : 'bg-green-600 hover:bg-green-700 text-white'
-            }`}
-            title={!isImageExists ? 'Docker image does not exist' : 'Run tests'}
-          >
-            {isCheckingImage ? (
-              <div className="animate-spin rounded-full h-4 w-4 border-b-2 border-gray-600"></div>
-            ) : (
-              <FiCheck size={16} />
-            )}
-            Test
-          </button>
+          <>
+            <button
+              onClick={handleTest}
+              disabled={!isImageExists || isCheckingImage || isTesting}
+              className={`px-6 py-2 font-medium rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-green-500 focus:ring-offset-2 transition-colors flex items-center gap-2 ${
+                !isImageExists || isCheckingImage || isTesting
+                  ? 'bg-gray-400 cursor-not-allowed text-gray-700'
+                  : 'bg-green-600 hover:bg-green-700 text-white'
+              }`}
+              title={!isImageExists ? 'Docker image does not exist' : 'Run tests'}
+            >
+              {isCheckingImage ? (
+                <div className="animate-spin rounded-full h-4 w-4 border-b-2 border-gray-600"></div>
+              ) : (
+                <FiCheck size={16} />
+              )}
+              Test
+            </button>
+            <button
+              onClick={handleVerify}
+              disabled={!isImageExists || isCheckingImage || !canVerify}
+              className={`px-6 py-2 font-medium rounded-md shadow-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 transition-colors flex items-center gap-2 ${
+                !isImageExists || isCheckingImage || !canVerify
+                  ? 'bg-gray-400 cursor-not-allowed text-gray-700'
+                  : 'bg-blue-600 hover:bg-blue-700 text-white'
+              }`}
+              title={
+                !canVerify
+                  ? 'Needs a patch and FAIL_TO_PASS or PASS_TO_PASS tests, import an instance first'
+                  : 'Run the tests with test_patch alone, then with the patch, and check FAIL_TO_PASS and PASS_TO_PASS'
+              }
+            >
+              <FiCheckCircle size={16} />
+              Verify
+            </button>
+          </>
         ) : (
           <button
             onClick={handleStopTest}
@@ -90,6 +168,80 @@ export default function TestSection({
         )}
       </div>
 
+      {/* Parsed test results, the failures listed by name */}
+      {!isTesting && testSummary && (
+        <div>
+          <div className="flex items-center gap-3 mb-2 text-sm">
+            <h3 className="text-lg font-medium text-gray-900 dark:text-white">
+              Results
+            </h3>
+            <span className="text-green-600 dark:text-green-400">{testSummary.passed} passed</span>
+            <span className="text-red-600 dark:text-red-400">{testSummary.failed} failed</span>
+            {testSummary.error > 0 && (
+              <span className="text-red-600 dark:text-red-400">{testSummary.error} errors</span>
+            )}
+            <span className="text-gray-500 dark:text-gray-400">{testSummary.skipped} skipped</span>
+          </div>
+          {testResults.some((result) => result.status === 'failed' || result.status === 'error') && (
+            <div className="border border-gray-200 dark:border-gray-700 rounded-md max-h-64 overflow-y-auto">
+              {testResults
+                .filter((result) => result.status === 'failed' || result.status === 'error')
+                .map((result, index) => (
+                  <div
+                    key={index}
+                    className="px-3 py-1 text-sm font-mono text-red-600 dark:text-red-400 truncate border-b last:border-b-0 border-gray-100 dark:border-gray-800"
+                    title={result.name}
+                  >
+                    {result.name}
+                  </div>
+                ))}
+            </div>
+          )}
+        </div>
+      )}
+
+      {/* Verification results, one row per FAIL_TO_PASS and PASS_TO_PASS test */}
+      {!isTesting && verdicts.length > 0 && (
+        <div>
+          <div className="flex items-center gap-2 mb-2">
+            <h3 className="text-lg font-medium text-gray-900 dark:text-white">
+              Verification
+            </h3>
+            <span className="text-sm text-gray-600 dark:text-gray-400">
+              {verdicts.filter((verdict) => verdict.success).length} of {verdicts.length} tests verified
+            </span>
+            {verifyReport && (
+              <button
+                onClick={handleCopyReport}
+                className="p-1 text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200 transition-colors"
+                title="Copy report.json"
+              >
+                <FiCopy size={16} />
+              </button>
+            )}
+          </div>
+          <div className="border border-gray-200 dark:border-gray-700 rounded-md max-h-64 overflow-y-auto">
+            {verdicts.map((verdict, index) => (
+              <div
+                key={index}
+                className="flex items-center gap-3 px-3 py-1 text-sm font-mono border-b last:border-b-0 border-gray-100 dark:border-gray-800"
+              >
+                <span className={verdict.success ? 'text-green-600 dark:text-green-400' : 'text-red-600 dark:text-red-400'}>
+                  {verdict.success ? 'OK' : 'FAIL'}
+                </span>
+                <span className="text-gray-500 dark:text-gray-400">{verdict.category}</span>
+                <span className="flex-1 truncate text-gray-900 dark:text-white" title={verdict.name}>
+                  {verdict.name}
+                </span>
+                <span className="text-gray-500 dark:text-gray-400">
+                  {verdict.before} → {verdict.after}
+                </span>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       {/* Test Logs Section */}
       {(isTesting || testLogs.length > 0) && (
         <div ref={testLogsSectionRef}>